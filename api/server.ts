@@ -1,13 +1,17 @@
 // Bun HTTP Server for doggy-bag
 // Handles backend API for IPC communication with Tauri frontend
-// In production: Uses OS-assigned port (port 0) and prints PORT=XXXX for Rust to capture
+// In production: Listens on the Unix socket passed as API_SOCKET, or falls back to an
+// OS-assigned port (port 0) and prints PORT=XXXX for Rust to capture
 // In development: Uses port 3000 for Vite proxy compatibility
 // Uses simple routing with Bun serve
 
 import { serve } from 'bun';
+import { chmodSync } from 'node:fs';
 import { routes } from './src/routes';
 import { StorageServiceImpl } from './src/services/storage';
 import { runWithRequestCache } from './src/services/request-cache';
+import { flushForShutdown } from './src/services/shutdown';
+import { isAuthorized } from './src/services/auth';
 
 // Check for version flag (used by Makefile to distinguish runtime from compiled binary)
 if (process.argv.includes('--version')) {
@@ -45,6 +49,13 @@ function log(level: 'INFO' | 'ERROR' | 'WARN' | 'DEBUG', message: string, ...arg
 // In production: Use port 0 to let OS assign an available port
 const PORT = isDevelopment ? 3000 : 0;
 
+// Per-launch token from the Tauri host (see src/services/auth.ts)
+const API_TOKEN = process.env.API_TOKEN || null;
+
+// Private socket path from the Tauri host (see src-tauri/src/api_socket.rs)
+// When set, nothing listens on a TCP port
+const API_SOCKET = process.env.API_SOCKET || null;
+
 const CORS_HEADERS = {
   'Access-Control-Allow-Origin': '*',
   'Access-Control-Allow-Methods': 'GET, POST, PUT, PATCH, DELETE, OPTIONS',
@@ -380,7 +391,7 @@ const sortedRoutes = [...routes].sort((a, b) => b.path.length - a.path.length);
 let server;
 try {
   server = serve({
-    port: PORT,
+    ...(API_SOCKET ? { unix: API_SOCKET } : { port: PORT }),
     async fetch(req) {
       const url = new URL(req.url);
       const path = url.pathname;
@@ -395,6 +406,11 @@ try {
         });
       }
 
+      if (!isAuthorized(req, API_TOKEN)) {
+        log('WARN', `Rejected unauthenticated ${req.method} ${path}`);
+        return corsResponse({ error: 'Unauthorized' }, 401);
+      }
+
       // Find matching route (pre-sorted by specificity at startup)
       for (const route of sortedRoutes) {
         const { path: routePath, definition } = route;
@@ -437,6 +453,10 @@ try {
       return corsResponse({ error: 'Not Found' }, 404);
     },
   });
+  if (API_SOCKET) {
+    // Only the user running the app may connect
+    chmodSync(API_SOCKET, 0o600);
+  }
 } catch (error) {
   // CRITICAL: Print PORT=ERROR so Rust can detect startup failure
   // instead of timing out silently
@@ -448,12 +468,28 @@ try {
 // Log startup info
 const storageConfig = StorageServiceImpl.getConfig();
 
-// CRITICAL: Print PORT=XXXX as the FIRST line for Rust to parse
-// This must be printed before any other log messages so Rust can capture it
-console.log(`PORT=${server.port}`);
+if (API_SOCKET) {
+  // The host already knows the socket path; it only waits for /api/health
+  log('INFO', `Bun backend server listening on ${API_SOCKET}`);
+} else {
+  // CRITICAL: Print PORT=XXXX as the FIRST line for Rust to parse
+  // This must be printed before any other log messages so Rust can capture it
+  console.log(`PORT=${server.port}`);
 
-log('INFO', `Bun backend server running on http://localhost:${server.port}`);
-log('INFO', `Health check: http://localhost:${server.port}/health`);
+  log('INFO', `Bun backend server running on http://localhost:${server.port}`);
+  log('INFO', `Health check: http://localhost:${server.port}/health`);
+}
 log('INFO', `Registered ${routes.length} routes`);
 log('INFO', `Data directory: ${storageConfig.basePath}`);
 log('INFO', `Mode: ${storageConfig.isDevelopment ? 'development' : 'production'}`);
+log('INFO', `API token: ${API_TOKEN ? 'required' : 'not configured'}`);
+
+// Flush pending writes before exiting on a termination signal
+// (the Tauri host sends SIGTERM if POST /api/shutdown did not stop us in time)
+for (const signal of ['SIGTERM', 'SIGINT'] as const) {
+  process.on(signal, async () => {
+    log('INFO', `Received ${signal}, shutting down...`);
+    await flushForShutdown(signal);
+    process.exit(0);
+  });
+}
@@ -1,3 +1,6 @@
+import { isCrossOrigin } from '../../services/auth';
+import { exitAfterResponse, flushForShutdown } from '../../services/shutdown';
+
 export function createHealthHandler() {
   return async () => {
     return new Response(
@@ -46,3 +49,38 @@ export function createHotReloadHandler() {
     );
   };
 }
+
+/**
+ * POST /api/shutdown
+ * Flushes pending writes, responds, then exits the process.
+ * Sent by the Tauri host before it stops the sidecar. CORS allows any origin,
+ * so requests from other web pages are refused.
+ */
+export function createShutdownHandler() {
+  return async (req: Request) => {
+    if (isCrossOrigin(req)) {
+      return new Response(
+        JSON.stringify({
+          error: 'Cross-origin shutdown requests are not allowed',
+        }),
+        {
+          headers: { 'Content-Type': 'application/json' },
+          status: 403,
+        }
+      );
+    }
+
+    await flushForShutdown('api');
+    exitAfterResponse(0);
+    return new Response(
+      JSON.stringify({
+        status: 'shutting_down',
+        timestamp: new Date().toISOString(),
+      }),
+      {
+        headers: { 'Content-Type': 'application/json' },
+        status: 200,
+      }
+    );
+  };
+}
@@ -1,6 +1,10 @@
 // Main routes file - Combined routes from all modules
 
-import { createHealthHandler, createTestHandler } from './handlers/common.handlers';
+import {
+  createHealthHandler,
+  createShutdownHandler,
+  createTestHandler,
+} from './handlers/common.handlers';
 
 import {
   createPaymentSourcesHandlerGET,
@@ -226,6 +230,7 @@ export const routes: Array<{ path: string; definition: RouteDefinition }> = [
   { path: '/api/health', definition: { method: 'GET', handler: createHealthHandler() } },
   { path: '/health', definition: { method: 'GET', handler: createHealthHandler() } },
   { path: '/api/test', definition: { method: 'GET', handler: createTestHandler() } },
+  { path: '/api/shutdown', definition: { method: 'POST', handler: createShutdownHandler() } },
 
   // Seed Defaults
   {
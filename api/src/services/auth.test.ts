@@ -0,0 +1,47 @@
+// Auth Service Tests
+import { describe, test, expect } from 'bun:test';
+import { isAuthorized, isCrossOrigin, requestToken } from './auth';
+
+const request = (path: string, headers: Record<string, string> = {}) =>
+  new Request(`http://localhost:1234${path}`, { headers });
+
+describe('AuthService', () => {
+  test('allows every request when no token is configured', () => {
+    expect(isAuthorized(request('/api/health'), null)).toBe(true);
+    expect(isAuthorized(request('/api/health'), '')).toBe(true);
+  });
+
+  test('accepts the token from the Authorization header', () => {
+    const req = request('/api/bills', { Authorization: 'Bearer secret-token' });
+    expect(requestToken(req)).toBe('secret-token');
+    expect(isAuthorized(req, 'secret-token')).toBe(true);
+  });
+
+  test('ignores a token in the query string', () => {
+    const req = request('/api/bills?token=secret-token');
+    expect(requestToken(req)).toBeNull();
+    expect(isAuthorized(req, 'secret-token')).toBe(false);
+  });
+
+  test('rejects a missing or wrong token', () => {
+    expect(isAuthorized(request('/api/bills'), 'secret-token')).toBe(false);
+    const wrong = request('/api/bills', { Authorization: 'Bearer nope' });
+    expect(isAuthorized(wrong, 'secret-token')).toBe(false);
+    expect(
+      isAuthorized(request('/api/bills', { Authorization: 'Basic secret-token' }), 'secret-token')
+    ).toBe(false);
+  });
+
+  test('treats requests without an Origin header as same-origin', () => {
+    expect(isCrossOrigin(request('/api/shutdown'))).toBe(false);
+    expect(isCrossOrigin(request('/api/shutdown', { Origin: 'http://localhost:1234' }))).toBe(
+      false
+    );
+  });
+
+  test('detects requests from other origins', () => {
+    expect(isCrossOrigin(request('/api/shutdown', { Origin: 'https://evil.example' }))).toBe(true);
+    expect(isCrossOrigin(request('/api/shutdown', { Origin: 'http://localhost:5173' }))).toBe(true);
+    expect(isCrossOrigin(request('/api/shutdown', { Origin: 'null' }))).toBe(true);
+  });
+});
@@ -0,0 +1,53 @@
+// Auth Service - Per-launch API token shared with the Tauri host
+//
+// The host generates a random token on every launch and passes it as API_TOKEN
+// next to DATA_DIR. Every request must then carry `Authorization: Bearer <token>`,
+// so other local processes and browser tabs that find the port cannot use the API.
+// Without API_TOKEN (browser development through the Vite proxy) nothing is checked.
+
+import { timingSafeEqual } from 'node:crypto';
+
+/**
+ * Token from the request's Authorization header. It is never read from the
+ * query string, where it would end up in history and logged URLs.
+ */
+export function requestToken(req: Request): string | null {
+  const header = req.headers.get('Authorization');
+  if (header?.startsWith('Bearer ')) {
+    return header.slice('Bearer '.length).trim();
+  }
+  return null;
+}
+
+/**
+ * Whether the request may use the API. Always true when no token is configured.
+ */
+export function isAuthorized(req: Request, expectedToken: string | null | undefined): boolean {
+  if (!expectedToken) {
+    return true;
+  }
+  const token = requestToken(req);
+  if (!token) {
+    return false;
+  }
+  const given = Buffer.from(token);
+  const expected = Buffer.from(expectedToken);
+  return given.length === expected.length && timingSafeEqual(given, expected);
+}
+
+/**
+ * Whether the request was sent by a web page on another origin. Browsers send
+ * `Origin` with every cross-origin POST; the Tauri host sends none.
+ */
+export function isCrossOrigin(req: Request): boolean {
+  const origin = req.headers.get('Origin');
+  if (!origin) {
+    return false;
+  }
+  try {
+    return new URL(origin).host !== new URL(req.url).host;
+  } catch {
+    // `Origin: null` from sandboxed pages and file:// URLs
+    return true;
+  }
+}
@@ -0,0 +1,56 @@
+// Shutdown Service Tests
+import { describe, test, expect, beforeAll, beforeEach, afterAll } from 'bun:test';
+import { flushForShutdown, isShuttingDown, resetShutdownState } from './shutdown';
+import { StorageServiceImpl } from './storage';
+import { mkdir, rm, readFile } from 'node:fs/promises';
+import { join } from 'node:path';
+import { tmpdir } from 'node:os';
+
+describe('ShutdownService', () => {
+  let testDir: string;
+
+  beforeAll(async () => {
+    testDir = join(tmpdir(), `shutdown-test-${Date.now()}`);
+    await mkdir(join(testDir, 'entities'), { recursive: true });
+    StorageServiceImpl.initialize(testDir);
+  });
+
+  beforeEach(() => {
+    resetShutdownState();
+  });
+
+  afterAll(async () => {
+    resetShutdownState();
+    try {
+      await rm(testDir, { recursive: true, force: true });
+    } catch {
+      // Ignore cleanup errors
+    }
+  });
+
+  test('is not shutting down before a flush is requested', () => {
+    expect(isShuttingDown()).toBe(false);
+  });
+
+  test('waits for in-flight writes to finish', async () => {
+    const storage = StorageServiceImpl.getInstance();
+    const data = [{ id: 'bill-1', name: 'Rent' }];
+
+    // Start a write but do not await it
+    const pending = storage.writeJSON('data/entities/bills.json', data);
+
+    await flushForShutdown('test');
+
+    const content = await readFile(join(testDir, 'entities', 'bills.json'), 'utf-8');
+    expect(JSON.parse(content)).toEqual(data);
+    await pending;
+  });
+
+  test('returns the same promise for repeated requests', () => {
+    const first = flushForShutdown('first');
+    const second = flushForShutdown('second');
+
+    expect(second).toBe(first);
+    expect(isShuttingDown()).toBe(true);
+  });
+});
@@ -0,0 +1,47 @@
+// Shutdown Service - Flush pending writes before the backend process exits
+//
+// Triggered by POST /api/shutdown (sent by the Tauri host before it stops the
+// sidecar) or by SIGTERM/SIGINT. Exiting only after the flush completes means a
+// stop never cuts a JSON write off halfway.
+
+import { AutoSaveServiceImpl } from './auto-save';
+import { StorageServiceImpl } from './storage';
+
+let shutdownPromise: Promise<void> | null = null;
+
+/**
+ * Flush queued auto-saves and wait for in-flight writes to finish.
+ * Safe to call more than once - later calls wait on the first flush.
+ */
+export function flushForShutdown(reason: string): Promise<void> {
+  if (!shutdownPromise) {
+    console.log(`[ShutdownService] Shutdown requested (${reason}) - flushing pending writes...`);
+    shutdownPromise = (async () => {
+      await AutoSaveServiceImpl.getInstance().shutdown();
+      await StorageServiceImpl.waitForPendingWrites();
+      console.log('[ShutdownService] All writes flushed');
+    })();
+  }
+  return shutdownPromise;
+}
+
+/**
+ * Whether a shutdown has been requested.
+ */
+export function isShuttingDown(): boolean {
+  return shutdownPromise !== null;
+}
+
+/**
+ * Exit the process on the next tick, after the current response has been sent.
+ */
+export function exitAfterResponse(code = 0): void {
+  setTimeout(() => process.exit(code), 50);
+}
+
+/**
+ * Reset shutdown state (for tests only).
+ */
+export function resetShutdownState(): void {
+  shutdownPromise = null;
+}
@@ -2,7 +2,7 @@
 import { describe, test, expect, beforeAll, afterAll } from 'bun:test';
 import { StorageServiceImpl } from './storage';
 import type { StorageService } from './storage';
-import { mkdir, rm, writeFile } from 'node:fs/promises';
+import { mkdir, readdir, rm, writeFile } from 'node:fs/promises';
 import { join } from 'node:path';
 import { tmpdir } from 'node:os';
 
@@ -139,6 +139,17 @@ describe('StorageService', () => {
 
       expect(result).toEqual(testData);
     });
+
+    test('replaces files atomically without leaving temp files behind', async () => {
+      const path = 'atomic/file.json';
+
+      await storage.writeFile(path, { version: 1 });
+      await storage.writeFile(path, { version: 2 });
+
+      const files = await readdir(join(testDir, 'atomic'));
+      expect(files).toEqual(['file.json']);
+      expect(await storage.readFile(path)).toEqual({ version: 2 });
+    });
   });
 
   describe('writeJSON and readJSON', () => {
@@ -167,6 +178,13 @@ describe('StorageService', () => {
       // Should not throw
       await storage.deleteFile('does-not-exist.json');
     });
+
+    test('does not leave temp files behind', async () => {
+      await storage.writeFile('deleted/file.json', { delete: 'me' });
+      await storage.deleteFile('deleted/file.json');
+
+      expect(await readdir(join(testDir, 'deleted'))).toEqual([]);
+    });
   });
 
   describe('fileExists', () => {
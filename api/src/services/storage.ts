@@ -1,4 +1,13 @@
-import { readFile, writeFile, unlink, access, mkdir, readdir, copyFile } from 'node:fs/promises';
+import {
+  readFile,
+  writeFile,
+  unlink,
+  access,
+  mkdir,
+  readdir,
+  copyFile,
+  rename,
+} from 'node:fs/promises';
 import { constants } from 'node:fs';
 import { join, dirname } from 'node:path';
 import { requestStorage } from './request-cache';
@@ -89,6 +98,20 @@ export class StorageServiceImpl implements StorageService {
     return StorageServiceImpl.instance;
   }
 
+  /**
+   * Wait until every in-flight write has completed.
+   * Used during graceful shutdown so the process never exits mid-write.
+   */
+  public static async waitForPendingWrites(): Promise<void> {
+    const instance = StorageServiceImpl.instance;
+    if (!instance) return;
+
+    // Writes queued while waiting are chained onto the map, so loop until it drains
+    while (instance.writeLocks.size > 0) {
+      await Promise.allSettled(Array.from(instance.writeLocks.values()));
+    }
+  }
+
   /**
    * Get the current storage configuration.
    */
@@ -179,7 +202,16 @@ export class StorageServiceImpl implements StorageService {
     await this.withWriteLock(resolvedPath, async () => {
       await this.ensureDirectory(dirname(resolvedPath));
       const content = JSON.stringify(data, null, 2);
-      await writeFile(resolvedPath, content, 'utf-8');
+      // Write to a temp file and rename it into place so a crash mid-write
+      // never leaves a truncated JSON file behind (rename is atomic on the same volume)
+      const tempPath = `${resolvedPath}.tmp-${process.pid}`;
+      try {
+        await writeFile(tempPath, content, 'utf-8');
+        await rename(tempPath, resolvedPath);
+      } catch (error) {
+        await unlink(tempPath).catch(() => {});
+        throw error;
+      }
     });
     // Invalidate cache after write completes so subsequent reads see fresh data
     requestStorage.getStore()?.delete(resolvedPath);
@@ -188,8 +220,12 @@ export class StorageServiceImpl implements StorageService {
   public async deleteFile(path: string): Promise<void> {
     const resolvedPath = this.resolvePath(path);
     await this.withWriteLock(resolvedPath, async () => {
+      // Rename to the same temp name writeFile uses before unlinking, so the
+      // host's data directory watcher recognizes the delete as our own
+      const tempPath = `${resolvedPath}.tmp-${process.pid}`;
       try {
-        await unlink(resolvedPath);
+        await rename(resolvedPath, tempPath);
+        await unlink(tempPath);
       } catch (error) {
         const errorMessage = error instanceof Error ? error.message : 'Unknown error';
         console.error(`[StorageService] Failed to delete file ${resolvedPath}:`, errorMessage);
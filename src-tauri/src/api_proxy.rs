@@ -0,0 +1,146 @@
+// `dbapi://` protocol forwarding the webview's API requests to the sidecar.
+//
+// The webview talks to a fixed origin (`dbapi://localhost`, or
+// `http://dbapi.localhost` on Windows) instead of the sidecar's port, so the
+// CSP does not have to allow any localhost port. Each request is forwarded
+// with its method, headers and body to the current sidecar (over its socket
+// when it has one, see api_socket.rs), with the per-launch API token added
+// (see api_token.rs). A request made while the sidecar is restarting waits
+// for it to come back, so a restart is invisible to the UI.
+
+use std::borrow::Cow;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{Manager, UriSchemeContext, UriSchemeResponder, Wry};
+use tracing::error;
+
+use crate::api_socket::Endpoint;
+use crate::sidecar_status::SidecarPhase;
+use crate::SidecarState;
+
+/// URI scheme served by the proxy
+pub(crate) const SCHEME: &str = "dbapi";
+
+/// How long a request waits for a starting or restarting sidecar
+const READY_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A forwarded request that takes longer than this is abandoned
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Request headers that belong to the webview's hop, not to the sidecar request
+const SKIPPED_REQUEST_HEADERS: [header::HeaderName; 5] = [
+    header::HOST,
+    header::ORIGIN,
+    header::CONNECTION,
+    header::CONTENT_LENGTH,
+    header::AUTHORIZATION,
+];
+
+/// HTTP client shared by forwarded requests (sends the API token)
+pub(crate) struct ProxyClient(reqwest::Client);
+
+impl ProxyClient {
+    pub(crate) fn new(app: &tauri::AppHandle) -> Result<ProxyClient, String> {
+        crate::api_token::client(app, REQUEST_TIMEOUT).map(ProxyClient)
+    }
+}
+
+/// Address of the usable sidecar, waiting while it starts or restarts
+async fn wait_for_endpoint(app: &tauri::AppHandle) -> Option<Endpoint> {
+    let deadline = Instant::now() + READY_TIMEOUT;
+    loop {
+        let (phase, endpoint) = {
+            let state = app.state::<Mutex<SidecarState>>();
+            let state_guard = state.lock().unwrap();
+            (state_guard.phase.clone(), state_guard.endpoint.clone())
+        };
+        match phase {
+            SidecarPhase::Ready | SidecarPhase::Degraded if endpoint.is_some() => return endpoint,
+            // Waiting on the user (profile, passphrase, lock) or given up: don't hold the request
+            SidecarPhase::Failed { .. }
+            | SidecarPhase::DataDirLocked { .. }
+            | SidecarPhase::VaultLocked
+            | SidecarPhase::ChoosingProfile => return None,
+            _ if Instant::now() >= deadline => return None,
+            _ => tokio::time::sleep(Duration::from_millis(100)).await,
+        }
+    }
+}
+
+fn error_response(status: StatusCode, error: &str) -> Response<Cow<'static, [u8]>> {
+    let body = serde_json::json!({ "error": error }).to_string();
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .body(Cow::Owned(body.into_bytes()))
+        .unwrap_or_default()
+}
+
+async fn forward(
+    app: &tauri::AppHandle,
+    request: Request<Vec<u8>>,
+) -> Result<Response<Cow<'static, [u8]>>, (StatusCode, String)> {
+    let Some(endpoint) = wait_for_endpoint(app).await else {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "The backend is not ready".to_string(),
+        ));
+    };
+    let path = request
+        .uri()
+        .path_and_query()
+        .map_or("/", |path| path.as_str());
+    let url = endpoint.url(path);
+
+    let client = app.state::<ProxyClient>().0.clone();
+    let (parts, body) = request.into_parts();
+    let mut forwarded = client.request(parts.method, &url).body(body);
+    for (name, value) in &parts.headers {
+        if !SKIPPED_REQUEST_HEADERS.contains(name) {
+            forwarded = forwarded.header(name, value);
+        }
+    }
+
+    let response = forwarded.send().await.map_err(|e| {
+        (
+            StatusCode::BAD_GATEWAY,
+            format!("Backend request failed: {}", e),
+        )
+    })?;
+    let mut builder = Response::builder().status(response.status());
+    for (name, value) in response.headers() {
+        if name != header::TRANSFER_ENCODING && name != header::CONNECTION {
+            builder = builder.header(name, value);
+        }
+    }
+    let body = response.bytes().await.map_err(|e| {
+        (
+            StatusCode::BAD_GATEWAY,
+            format!("Backend response failed: {}", e),
+        )
+    })?;
+    builder
+        .body(Cow::Owned(body.to_vec()))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Protocol handler registered for `dbapi://`
+pub(crate) fn handle(
+    ctx: UriSchemeContext<'_, Wry>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let app = ctx.app_handle().clone();
+    tauri::async_runtime::spawn(async move {
+        let response = match forward(&app, request).await {
+            Ok(response) => response,
+            Err((status, error)) => {
+                error!("[ApiProxy] {}", error);
+                error_response(status, &error)
+            }
+        };
+        responder.respond(response);
+    });
+}
@@ -0,0 +1,123 @@
+// Private Unix domain socket transport for the sidecar.
+//
+// On Linux and macOS the host creates a directory only the user can enter
+// (under $XDG_RUNTIME_DIR, or the per-user temp dir on macOS) and passes a
+// socket path inside it to the sidecar as API_SOCKET. The sidecar binds that
+// socket with mode 0600 instead of a TCP port, and every host request
+// (readiness and health probes, the dbapi:// proxy, shutdown, tray,
+// reminders) goes over it. Nothing listens on the network, and the host knows
+// the address up front instead of reading `PORT=<port>` from stdout.
+//
+// Windows, or a directory that cannot be created, falls back to the
+// OS-assigned localhost port. The headless commands (cli.rs) keep TCP too,
+// since `serve` exists to be reachable by other programs.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Environment variable the sidecar reads the socket path from
+pub(crate) const SOCKET_ENV: &str = "API_SOCKET";
+
+/// Longest socket path every supported platform accepts (macOS sun_path is 104 bytes)
+#[cfg(unix)]
+const MAX_SOCKET_PATH: usize = 103;
+
+/// Socket path of this launch (None when the sidecar uses a TCP port)
+pub(crate) struct ApiSocket(Option<PathBuf>);
+
+impl ApiSocket {
+    /// Create the private socket directory, or fall back to TCP
+    pub(crate) fn create() -> ApiSocket {
+        #[cfg(unix)]
+        match socket_path() {
+            Ok(path) => {
+                info!("[ApiSocket] Sidecar will listen on {}", path.display());
+                return ApiSocket(Some(path));
+            }
+            Err(e) => warn!("[ApiSocket] {}, falling back to a TCP port", e),
+        }
+        ApiSocket(None)
+    }
+
+    pub(crate) fn path(&self) -> Option<&Path> {
+        self.0.as_deref()
+    }
+
+    /// Remove a socket left behind by a sidecar that crashed, before spawning the next one
+    pub(crate) fn clear(&self) {
+        if let Some(path) = &self.0 {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    /// Remove the socket directory when the app exits
+    pub(crate) fn remove(&self) {
+        if let Some(dir) = self.0.as_deref().and_then(Path::parent) {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// Socket path inside a new directory only this user can enter
+#[cfg(unix)]
+fn socket_path() -> Result<PathBuf, String> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    let base = dirs::runtime_dir().unwrap_or_else(std::env::temp_dir);
+    let dir = base.join(format!("doggybag-{}", std::process::id()));
+    let path = dir.join("api.sock");
+    if path.as_os_str().len() > MAX_SOCKET_PATH {
+        return Err(format!("Socket path {} is too long", path.display()));
+    }
+    // Leftover from an earlier process with the same PID
+    let _ = std::fs::remove_dir_all(&dir);
+    // Fails if anything (such as a planted symlink) took the name in the meantime
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(path)
+}
+
+/// Where the current sidecar listens
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Endpoint {
+    /// Private socket created by the host (see ApiSocket)
+    Socket(PathBuf),
+    /// Localhost port the sidecar reported as `PORT=<port>`
+    Port(u16),
+}
+
+impl Endpoint {
+    /// URL of `path` on this sidecar (the host part is not used for a socket)
+    pub(crate) fn url(&self, path: &str) -> String {
+        match self {
+            Endpoint::Socket(_) => format!("http://localhost{}", path),
+            Endpoint::Port(port) => format!("http://localhost:{}{}", port, path),
+        }
+    }
+
+    pub(crate) fn port(&self) -> Option<u16> {
+        match self {
+            Endpoint::Socket(_) => None,
+            Endpoint::Port(port) => Some(*port),
+        }
+    }
+
+    pub(crate) fn socket_path(&self) -> Option<&Path> {
+        match self {
+            Endpoint::Socket(path) => Some(path),
+            Endpoint::Port(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Endpoint::Socket(path) => write!(f, "socket {}", path.display()),
+            Endpoint::Port(port) => write!(f, "port {}", port),
+        }
+    }
+}
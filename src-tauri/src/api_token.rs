@@ -0,0 +1,103 @@
+// Per-launch secret guarding the sidecar API.
+//
+// The sidecar listens on a localhost port that any local process or browser
+// tab could find. A random token is generated on every launch and passed to
+// the sidecar as API_TOKEN next to DATA_DIR; the backend rejects requests
+// without `Authorization: Bearer <token>`. The webview gets the token only
+// through `get_sidecar_credentials`, and every host-to-backend request goes
+// through `client()`, which sends it by default (over the sidecar's socket
+// when it has one, see api_socket.rs).
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::api_socket::ApiSocket;
+use crate::sidecar_status::SidecarPhase;
+use crate::SidecarState;
+
+/// Environment variable the sidecar reads the token from
+pub(crate) const TOKEN_ENV: &str = "API_TOKEN";
+
+/// Token of this launch (shared by every sidecar the app starts)
+pub(crate) struct ApiToken(String);
+
+impl ApiToken {
+    pub(crate) fn generate() -> ApiToken {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        ApiToken(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    pub(crate) fn value(&self) -> &str {
+        &self.0
+    }
+}
+
+/// HTTP client for backend requests that sends `token` with every request,
+/// connecting through `socket` when the backend listens on one
+pub(crate) fn client_with_token(
+    token: &str,
+    socket: Option<&Path>,
+    timeout: Duration,
+) -> Result<reqwest::Client, String> {
+    let mut auth = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token))
+        .map_err(|e| format!("Invalid API token: {}", e))?;
+    auth.set_sensitive(true);
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(reqwest::header::AUTHORIZATION, auth);
+    let builder = reqwest::Client::builder()
+        .default_headers(headers)
+        .timeout(timeout);
+    #[cfg(unix)]
+    let builder = match socket {
+        Some(socket) => builder.unix_socket(socket),
+        None => builder,
+    };
+    #[cfg(not(unix))]
+    let _ = socket;
+    builder
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// HTTP client for the app's sidecar (over its socket, see api_socket.rs)
+pub(crate) fn client(app: &tauri::AppHandle, timeout: Duration) -> Result<reqwest::Client, String> {
+    client_with_token(
+        app.state::<ApiToken>().value(),
+        app.state::<ApiSocket>().path(),
+        timeout,
+    )
+}
+
+/// Where and how the webview reaches the backend
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SidecarCredentials {
+    /// None when the backend listens on a socket (reached through dbapi://)
+    pub port: Option<u16>,
+    pub token: String,
+}
+
+/// Port and token of the backend (fails until the sidecar is ready)
+#[tauri::command]
+pub(crate) fn get_sidecar_credentials(app: tauri::AppHandle) -> Result<SidecarCredentials, String> {
+    let endpoint = {
+        let state = app.state::<Mutex<SidecarState>>();
+        let state_guard = state.lock().unwrap();
+        match state_guard.phase {
+            SidecarPhase::Ready | SidecarPhase::Degraded => state_guard.endpoint.clone(),
+            _ => None,
+        }
+    };
+    let endpoint = endpoint.ok_or("The backend is not ready")?;
+    Ok(SidecarCredentials {
+        port: endpoint.port(),
+        token: app.state::<ApiToken>().value().to_string(),
+    })
+}
@@ -0,0 +1,593 @@
+// Headless command line mode of the doggybag binary.
+//
+//   doggybag export [--out backup.json]    full backup as JSON (stdout without --out)
+//   doggybag import <backup.json>          replace the data with a backup (`-` reads stdin)
+//   doggybag verify                        check the data files (exit code 1 on problems)
+//   doggybag snapshot                      take a manual snapshot (see snapshots.rs)
+//   doggybag leftover [--month 2026-01]    leftover of a month (default: this month)
+//   doggybag serve                         run the backend until interrupted
+//
+// Every command takes --data-dir <dir> (default: $DATA_DIR, then the app's
+// saved data directory, then ~/Documents/DoggyBag), --json for machine
+// readable output and --verbose to show the backend's output.
+//
+// No window or webview is created, so the commands work from cron and over
+// SSH. The sidecar is spawned directly as a child process (the shell plugin
+// needs a running app) under the same data directory lock as the app, so a
+// command fails instead of running next to an open app. An encrypted data
+// directory is refused: the vault can only be unlocked in the app.
+//
+// Results go to stdout, diagnostics to stderr. Any other first argument
+// (including a doggybag:// link) starts the app as usual.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::api_socket::Endpoint;
+use crate::api_token::{self, ApiToken};
+use crate::data_dir_lock::{HeadlessLock, LockError};
+use crate::snapshots::SnapshotReason;
+
+/// Subcommands handled here instead of starting the app
+const COMMANDS: [&str; 6] = [
+    "export", "import", "verify", "snapshot", "leftover", "serve",
+];
+
+/// Identifier from tauri.conf.json; the app's config dir is named after it
+const APP_IDENTIFIER: &str = "com.bradhannah.doggybag";
+
+/// Time the backend gets to print its port
+const PORT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Time the backend gets to answer its health check once the port is known
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(6);
+
+/// A request that takes longer than this is abandoned
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Time the backend gets to flush and exit after /api/shutdown
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+const USAGE: &str = "Usage: doggybag <command> [options]
+
+Commands:
+  export [--out <file>]      Write a full backup as JSON (stdout without --out)
+  import <file>              Replace the data with a backup (- reads stdin)
+  verify                     Check the data files; exits with 1 on problems
+  snapshot                   Take a manual snapshot of the data directory
+  leftover [--month YYYY-MM] Show the leftover of a month (default: this month)
+  serve                      Run the backend until interrupted
+
+Options:
+  --data-dir <dir>  Data directory (default: $DATA_DIR, then the app's setting)
+  --json            Print JSON instead of a table
+  --verbose         Show the backend's output on stderr
+
+Without a command the app starts as usual.";
+
+#[derive(Default)]
+struct Options {
+    command: String,
+    data_dir: Option<String>,
+    out: Option<String>,
+    input: Option<String>,
+    month: Option<String>,
+    json: bool,
+    verbose: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        command: args[0].clone(),
+        ..Options::default()
+    };
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        let mut value = |flag: &str| {
+            rest.next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value", flag))
+        };
+        match arg.as_str() {
+            "--data-dir" => options.data_dir = Some(value(arg)?),
+            "--out" if options.command == "export" => options.out = Some(value(arg)?),
+            "--month" if options.command == "leftover" => options.month = Some(value(arg)?),
+            "--json" => options.json = true,
+            "--verbose" => options.verbose = true,
+            _ if options.command == "import"
+                && options.input.is_none()
+                && !arg.starts_with("--") =>
+            {
+                options.input = Some(arg.clone())
+            }
+            _ => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+    if options.command == "import" && options.input.is_none() {
+        return Err("import needs a backup file (or - for stdin)".to_string());
+    }
+    Ok(options)
+}
+
+/// Run a subcommand and return its exit code, or None when `args` is not a
+/// subcommand and the app should start
+pub(crate) fn run(args: Vec<String>) -> Option<i32> {
+    let command = args.first()?;
+    if matches!(command.as_str(), "help" | "--help" | "-h") {
+        println!("{}", USAGE);
+        return Some(0);
+    }
+    if !COMMANDS.contains(&command.as_str()) {
+        return None;
+    }
+
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("doggybag: {}\n\n{}", e, USAGE);
+            return Some(2);
+        }
+    };
+    // Messages from the shared modules (lock, snapshots) only show with --verbose
+    if options.verbose {
+        crate::logging::init_stderr();
+    }
+    match run_command(&options) {
+        Ok(code) => Some(code),
+        Err(e) => {
+            eprintln!("doggybag {}: {}", options.command, e);
+            Some(1)
+        }
+    }
+}
+
+fn run_command(options: &Options) -> Result<i32, String> {
+    let data_dir = data_dir(options)?;
+    match options.command.as_str() {
+        "export" => export(options, &data_dir),
+        "import" => import(options, &data_dir),
+        "verify" => verify(options, &data_dir),
+        "snapshot" => snapshot(options, &data_dir),
+        "leftover" => leftover(options, &data_dir),
+        "serve" => serve(options, &data_dir),
+        _ => unreachable!("checked against COMMANDS"),
+    }
+}
+
+/// The app's config dir (what `app_config_dir()` returns inside the app)
+fn config_dir() -> Result<PathBuf, String> {
+    dirs::config_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| "Could not determine the config directory".to_string())
+}
+
+/// `dataDirectory` from the app's settings (the active profile's directory)
+fn saved_data_dir() -> Option<String> {
+    let content = std::fs::read_to_string(config_dir().ok()?.join("settings.json")).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+    json.get("dataDirectory")?.as_str().map(str::to_string)
+}
+
+fn data_dir(options: &Options) -> Result<PathBuf, String> {
+    let dir = options
+        .data_dir
+        .clone()
+        .or_else(|| std::env::var("DATA_DIR").ok())
+        .or_else(saved_data_dir);
+    let dir = PathBuf::from(crate::resolve_data_dir(dir)?);
+    if crate::vault::is_vault(&dir) {
+        return Err(format!(
+            "{} is encrypted; open it in the app to unlock it",
+            dir.display()
+        ));
+    }
+    Ok(dir)
+}
+
+fn take_lock(data_dir: &Path) -> Result<HeadlessLock, String> {
+    HeadlessLock::acquire(data_dir).map_err(|e| match e {
+        LockError::Held(holder) => format!(
+            "Data directory is in use by {} (PID {})",
+            holder.hostname, holder.pid
+        ),
+        LockError::Io(e) => e,
+    })
+}
+
+fn print_json(value: &impl serde::Serialize) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize output: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// Sidecar started for a single command; stopped (gracefully) when dropped
+struct HeadlessSidecar {
+    child: Child,
+    port: u16,
+    token: ApiToken,
+    client: reqwest::Client,
+    // Declared last so the lock is released after the sidecar has exited
+    lock: HeadlessLock,
+}
+
+impl HeadlessSidecar {
+    fn start(data_dir: &Path, verbose: bool) -> Result<HeadlessSidecar, String> {
+        let lock = take_lock(data_dir)?;
+        for sub_dir in ["entities", "months"] {
+            let dir = data_dir.join(sub_dir);
+            std::fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+
+        // Bundles and cargo builds both put the sidecar next to the executable
+        let exe = std::env::current_exe()
+            .map_err(|e| format!("Failed to locate the executable: {}", e))?;
+        let exe_dir = exe
+            .parent()
+            .ok_or("Failed to locate the executable's directory")?;
+        let program = exe_dir.join(format!("bun-sidecar{}", std::env::consts::EXE_SUFFIX));
+        let token = ApiToken::generate();
+        let client = api_token::client_with_token(token.value(), None, REQUEST_TIMEOUT)?;
+        let mut command = Command::new(&program);
+        if let Some(server_script) = crate::dev_server_script(exe_dir) {
+            command.arg("run").arg(server_script);
+        }
+        let mut child = command
+            .env("DATA_DIR", data_dir)
+            .env(api_token::TOKEN_ENV, token.value())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(if verbose {
+                Stdio::inherit()
+            } else {
+                Stdio::null()
+            })
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {}", program.display(), e))?;
+
+        // The first PORT= line carries the port; the rest is only shown with --verbose
+        let stdout = child.stdout.take().ok_or("Sidecar has no stdout")?;
+        let (port_tx, port_rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if let Some(port) = line.strip_prefix("PORT=") {
+                    let _ = port_tx.send(port.trim().to_string());
+                } else if verbose {
+                    eprintln!("[Sidecar] {}", line);
+                }
+            }
+        });
+
+        let port = port_rx
+            .recv_timeout(PORT_TIMEOUT)
+            .ok()
+            .and_then(|port| port.parse::<u16>().ok());
+        let Some(port) = port else {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err("Backend failed to start (run with --verbose for its output)".to_string());
+        };
+
+        let sidecar = HeadlessSidecar {
+            child,
+            port,
+            token,
+            client,
+            lock,
+        };
+        sidecar.wait_for_health()?;
+        if verbose {
+            eprintln!("[CLI] Backend is ready on port {}", port);
+        }
+        Ok(sidecar)
+    }
+
+    fn wait_for_health(&self) -> Result<(), String> {
+        let deadline = Instant::now() + HEALTH_TIMEOUT;
+        let url = self.url("/api/health");
+        tauri::async_runtime::block_on(async {
+            loop {
+                match self.client.get(&url).send().await {
+                    Ok(response) if response.status().is_success() => return Ok(()),
+                    _ if Instant::now() >= deadline => {
+                        return Err("Backend failed to respond to health check".to_string())
+                    }
+                    _ => tokio::time::sleep(Duration::from_millis(200)).await,
+                }
+            }
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("http://localhost:{}{}", self.port, path)
+    }
+
+    fn get(&self, path: &str) -> Result<serde_json::Value, String> {
+        send(self.client.get(self.url(path)))
+    }
+
+    fn post(&self, path: &str, body: &serde_json::Value) -> Result<serde_json::Value, String> {
+        send(self.client.post(self.url(path)).json(body))
+    }
+}
+
+impl Drop for HeadlessSidecar {
+    fn drop(&mut self) {
+        if matches!(self.child.try_wait(), Ok(Some(_))) {
+            return;
+        }
+        // Same escalation as shutdown.rs: ask the backend to flush and exit, then kill it
+        let requested = tauri::async_runtime::block_on(crate::shutdown::request_shutdown(
+            &Endpoint::Port(self.port),
+            self.token.value(),
+            SHUTDOWN_TIMEOUT,
+        ));
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+        while requested.is_ok() && Instant::now() < deadline {
+            if matches!(self.child.try_wait(), Ok(Some(_))) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        eprintln!("[CLI] Backend did not exit in time, killing it");
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Send a request and return the JSON body, turning error responses into their message
+fn send(request: reqwest::RequestBuilder) -> Result<serde_json::Value, String> {
+    tauri::async_runtime::block_on(async {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.unwrap_or(serde_json::Value::Null);
+        if status.is_success() {
+            return Ok(body);
+        }
+        let error = body
+            .get("error")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("Backend returned {}", status));
+        match body.get("details") {
+            Some(serde_json::Value::String(details)) => Err(format!("{}: {}", error, details)),
+            Some(serde_json::Value::Array(details)) => {
+                let details: Vec<String> = details
+                    .iter()
+                    .map(|d| d.as_str().map_or_else(|| d.to_string(), str::to_string))
+                    .collect();
+                Err(format!("{}: {}", error, details.join("; ")))
+            }
+            _ => Err(error),
+        }
+    })
+}
+
+fn export(options: &Options, data_dir: &Path) -> Result<i32, String> {
+    let sidecar = HeadlessSidecar::start(data_dir, options.verbose)?;
+    let backup = sidecar.get("/api/backup")?;
+    drop(sidecar);
+
+    let json = serde_json::to_string_pretty(&backup)
+        .map_err(|e| format!("Failed to serialize backup: {}", e))?;
+    match &options.out {
+        Some(out) => {
+            std::fs::write(out, json).map_err(|e| format!("Failed to write {}: {}", out, e))?;
+            eprintln!("Exported {} to {}", data_dir.display(), out);
+        }
+        None => println!("{}", json),
+    }
+    Ok(0)
+}
+
+fn import(options: &Options, data_dir: &Path) -> Result<i32, String> {
+    let input = options.input.as_deref().unwrap_or("-");
+    let mut content = String::new();
+    if input == "-" {
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .map_err(|e| format!("Failed to read stdin: {}", e))?;
+    } else {
+        content = std::fs::read_to_string(input)
+            .map_err(|e| format!("Failed to read {}: {}", input, e))?;
+    }
+    let backup: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("{} is not valid JSON: {}", input, e))?;
+
+    let sidecar = HeadlessSidecar::start(data_dir, options.verbose)?;
+    sidecar.post("/api/backup/validate", &backup)?;
+
+    // Same safety net as a restore in the app: the data can be rolled back from the snapshot
+    let snapshot = crate::snapshots::write_snapshot(
+        &crate::snapshots::snapshots_dir_in(&config_dir()?),
+        data_dir,
+        SnapshotReason::PreRestore,
+        env!("CARGO_PKG_VERSION"),
+    )?;
+    eprintln!("Saved the current data as snapshot {}", snapshot.id);
+
+    let result = sidecar.post("/api/backup", &backup)?;
+    drop(sidecar);
+
+    if options.json {
+        print_json(&result)?;
+    } else if let Some(imported) = result.get("imported").and_then(|v| v.as_object()) {
+        println!("Imported into {}", data_dir.display());
+        for (kind, count) in imported {
+            println!("  {:<16} {}", kind, count);
+        }
+    }
+    Ok(0)
+}
+
+fn verify(options: &Options, data_dir: &Path) -> Result<i32, String> {
+    use crate::data_integrity::FileStatus;
+
+    let report = crate::data_integrity::verify_data_dir(data_dir);
+    if options.json {
+        print_json(&report)?;
+    } else {
+        for file in &report.files {
+            let status = match &file.status {
+                FileStatus::Ok => "ok".to_string(),
+                FileStatus::Empty => "empty".to_string(),
+                FileStatus::Corrupt { error } => format!("invalid JSON: {}", error),
+                FileStatus::UnexpectedShape { expected } => format!("expected {}", expected),
+                FileStatus::Unreadable { error } => format!("unreadable: {}", error),
+            };
+            println!("{:<40} {}", file.path, status);
+        }
+        println!(
+            "{} files checked in {}, {} problems",
+            report.files.len(),
+            report.data_dir,
+            report.problems
+        );
+    }
+    Ok(if report.is_healthy() { 0 } else { 1 })
+}
+
+fn snapshot(options: &Options, data_dir: &Path) -> Result<i32, String> {
+    // Hold the lock so the app cannot write while the archive is taken
+    let _lock = take_lock(data_dir)?;
+    let dir = crate::snapshots::snapshots_dir_in(&config_dir()?);
+    let info = crate::snapshots::write_snapshot(
+        &dir,
+        data_dir,
+        SnapshotReason::Manual,
+        env!("CARGO_PKG_VERSION"),
+    )?;
+    if options.json {
+        print_json(&info)?;
+    } else {
+        println!(
+            "Snapshot {} ({} files, {} bytes) saved to {}",
+            info.id,
+            info.file_count,
+            info.size_bytes,
+            dir.display()
+        );
+    }
+    Ok(0)
+}
+
+fn leftover(options: &Options, data_dir: &Path) -> Result<i32, String> {
+    let month = match &options.month {
+        Some(month) => {
+            crate::deep_link::check_month(month)?;
+            month.clone()
+        }
+        None => chrono::Local::now().format("%Y-%m").to_string(),
+    };
+
+    let sidecar = HeadlessSidecar::start(data_dir, options.verbose)?;
+    let detailed = sidecar
+        .get(&format!("/api/months/{}/detailed", month))
+        .map_err(|e| format!("No data for {} ({})", month, e))?;
+    drop(sidecar);
+
+    let breakdown = detailed
+        .get("leftoverBreakdown")
+        .cloned()
+        .ok_or("Backend response has no leftoverBreakdown")?;
+    if options.json {
+        let mut output = serde_json::json!({ "month": month });
+        if let (Some(output), Some(breakdown)) = (output.as_object_mut(), breakdown.as_object()) {
+            output.extend(breakdown.clone());
+        }
+        return print_json(&output).map(|_| 0);
+    }
+
+    let cents = |key: &str| {
+        breakdown.get(key).and_then(|v| v.as_f64()).map_or_else(
+            || "-".to_string(),
+            |v| crate::tray::format_cents(v.round() as i64),
+        )
+    };
+    println!("{:<20} {}", "Month", month);
+    println!("{:<20} {}", "Bank balances", cents("bankBalances"));
+    println!("{:<20} {}", "Remaining income", cents("remainingIncome"));
+    println!(
+        "{:<20} {}",
+        "Remaining expenses",
+        cents("remainingExpenses")
+    );
+    if breakdown.get("isValid").and_then(|v| v.as_bool()) == Some(false) {
+        let reason = breakdown
+            .get("errorMessage")
+            .and_then(|v| v.as_str())
+            .unwrap_or("bank balances are missing");
+        println!("{:<20} - ({})", "Leftover", reason);
+    } else {
+        println!("{:<20} {}", "Leftover", cents("leftover"));
+    }
+    Ok(0)
+}
+
+fn serve(options: &Options, data_dir: &Path) -> Result<i32, String> {
+    let mut sidecar = HeadlessSidecar::start(data_dir, options.verbose)?;
+    if options.json {
+        print_json(&serde_json::json!({
+            "port": sidecar.port,
+            "dataDir": data_dir.to_string_lossy(),
+        }))?;
+    } else {
+        println!(
+            "Serving {} on http://localhost:{} (Ctrl+C to stop)",
+            data_dir.display(),
+            sidecar.port
+        );
+    }
+    let _ = std::io::stdout().flush();
+
+    let result = tauri::async_runtime::block_on(async {
+        let mut last_heartbeat = Instant::now();
+        let mut tick = tokio::time::interval(Duration::from_secs(1));
+        let stop = wait_for_stop_signal();
+        tokio::pin!(stop);
+        loop {
+            tokio::select! {
+                _ = &mut stop => return Ok(()),
+                _ = tick.tick() => {}
+            }
+            if let Ok(Some(status)) = sidecar.child.try_wait() {
+                return Err(format!("Backend exited ({})", status));
+            }
+            if last_heartbeat.elapsed() >= HeadlessLock::interval() {
+                last_heartbeat = Instant::now();
+                if let Some(holder) = sidecar.lock.heartbeat() {
+                    return Err(format!(
+                        "Data directory was taken over by {} (PID {})",
+                        holder.hostname, holder.pid
+                    ));
+                }
+            }
+        }
+    });
+    eprintln!("[CLI] Stopping backend");
+    drop(sidecar);
+    result.map(|_| 0)
+}
+
+/// Resolves on Ctrl+C, or SIGTERM from a service manager
+async fn wait_for_stop_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        if let Ok(mut sigterm) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = sigterm.recv() => {}
+            }
+            return;
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
@@ -0,0 +1,352 @@
+// Advisory lock on the data directory.
+//
+// Two sidecars writing the same DATA_DIR silently clobber each other's
+// writes. Before a sidecar is spawned the host writes `.doggybag.lock` into
+// DATA_DIR with its hostname, PID and a heartbeat that is refreshed every
+// 30 seconds. A lock held by a live process on this machine, or with a
+// recent heartbeat from another machine (the folder may be shared through a
+// cloud drive), blocks the start: the sidecar moves to DataDirLocked and a
+// `data-dir-locked` event offers the user to take over. A second launch on
+// the same machine never gets this far - tauri-plugin-single-instance
+// focuses the existing window instead.
+//
+// An instance whose lock was taken over notices on its next heartbeat and
+// stops its sidecar.
+//
+// The headless CLI (cli.rs) takes the same lock through HeadlessLock, so a
+// cron job never runs against a directory the app is using.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tracing::{error, info};
+
+use crate::sidecar_status::{self, now_millis, SidecarPhase};
+
+/// Lock file name inside the data directory
+const LOCK_FILE: &str = ".doggybag.lock";
+
+/// How often the holder refreshes its heartbeat
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A lock whose heartbeat is older than this is considered abandoned
+const STALE_AFTER: Duration = Duration::from_secs(120);
+
+/// Contents of the lock file
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LockInfo {
+    /// Random per-launch id, so a takeover can be told apart from our own lock
+    pub instance_id: String,
+    pub hostname: String,
+    pub pid: u32,
+    pub app_version: String,
+    /// Unix timestamp (ms) when the lock was taken
+    pub acquired_at: u64,
+    /// Unix timestamp (ms) of the last heartbeat
+    pub heartbeat_at: u64,
+}
+
+/// The lock this instance holds, if any
+pub(crate) struct DataDirLockState {
+    instance_id: String,
+    held: Option<PathBuf>,
+}
+
+impl Default for DataDirLockState {
+    fn default() -> Self {
+        DataDirLockState {
+            instance_id: format!("{}-{:x}", std::process::id(), now_millis()),
+            held: None,
+        }
+    }
+}
+
+pub(crate) enum LockError {
+    /// Another live instance holds the lock
+    Held(LockInfo),
+    Io(String),
+}
+
+fn hostname() -> String {
+    gethostname::gethostname().to_string_lossy().to_string()
+}
+
+fn lock_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(LOCK_FILE)
+}
+
+fn read_lock(path: &Path) -> Option<LockInfo> {
+    let content = std::fs::read(path).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+/// Write the lock file through a temp file so a half-written lock is never read
+fn write_lock(path: &Path, info: &LockInfo) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(info)
+        .map_err(|e| format!("Failed to serialize lock: {}", e))?;
+    let temp_path = PathBuf::from(format!("{}.tmp", path.to_string_lossy()));
+    std::fs::write(&temp_path, content).map_err(|e| format!("Failed to write lock file: {}", e))?;
+    std::fs::rename(&temp_path, path).map_err(|e| {
+        let _ = std::fs::remove_file(&temp_path);
+        format!("Failed to write lock file: {}", e)
+    })
+}
+
+/// Whether a process with `pid` is running on this machine
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    use nix::errno::Errno;
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+
+    // Signal 0 only checks for existence; EPERM means it exists but belongs to someone else
+    matches!(
+        kill(Pid::from_raw(pid as i32), None),
+        Ok(()) | Err(Errno::EPERM)
+    )
+}
+
+/// Without a cheap liveness check, rely on the heartbeat alone
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+/// Whether `lock` still belongs to a running instance
+fn is_live(lock: &LockInfo) -> bool {
+    let fresh = now_millis().saturating_sub(lock.heartbeat_at) < STALE_AFTER.as_millis() as u64;
+    if lock.hostname == hostname() {
+        fresh && process_alive(lock.pid)
+    } else {
+        fresh
+    }
+}
+
+/// Another live instance holding the lock on `data_dir`, if any
+pub(crate) fn current_holder(app: &tauri::AppHandle, data_dir: &Path) -> Option<LockInfo> {
+    let lock = read_lock(&lock_path(data_dir))?;
+    let state = app.state::<Mutex<DataDirLockState>>();
+    let state_guard = state.lock().unwrap();
+    (lock.instance_id != state_guard.instance_id && is_live(&lock)).then_some(lock)
+}
+
+/// Take the lock on `data_dir` (refreshing it if we already hold it)
+/// With `force`, a live lock held by another instance is taken over.
+pub(crate) fn acquire(
+    app: &tauri::AppHandle,
+    data_dir: &str,
+    force: bool,
+) -> Result<(), LockError> {
+    let data_path = PathBuf::from(data_dir);
+    std::fs::create_dir_all(&data_path)
+        .map_err(|e| LockError::Io(format!("Failed to create data directory: {}", e)))?;
+    let path = lock_path(&data_path);
+
+    let state = app.state::<Mutex<DataDirLockState>>();
+    let mut state_guard = state.lock().unwrap();
+
+    // Release a lock on a previous data directory
+    if let Some(previous) = state_guard.held.take() {
+        if previous != path {
+            release_file(&previous, &state_guard.instance_id);
+        }
+    }
+
+    if let Some(existing) = read_lock(&path) {
+        if existing.instance_id != state_guard.instance_id && is_live(&existing) {
+            if !force {
+                return Err(LockError::Held(existing));
+            }
+            info!(
+                "[Lock] Taking over data directory from {} (PID {})",
+                existing.hostname, existing.pid
+            );
+        }
+    }
+
+    let now = now_millis();
+    let info = LockInfo {
+        instance_id: state_guard.instance_id.clone(),
+        hostname: hostname(),
+        pid: std::process::id(),
+        app_version: app.package_info().version.to_string(),
+        acquired_at: now,
+        heartbeat_at: now,
+    };
+    write_lock(&path, &info).map_err(LockError::Io)?;
+    state_guard.held = Some(path);
+    Ok(())
+}
+
+/// Remove the lock file if it is still ours
+fn release_file(path: &Path, instance_id: &str) {
+    if read_lock(path).is_some_and(|lock| lock.instance_id == instance_id) {
+        if let Err(e) = std::fs::remove_file(path) {
+            info!("[Lock] Failed to remove lock file: {}", e);
+        }
+    }
+}
+
+/// Release the lock held by this instance (when the sidecar is stopped)
+pub(crate) fn release(app: &tauri::AppHandle) {
+    let state = app.state::<Mutex<DataDirLockState>>();
+    let mut state_guard = state.lock().unwrap();
+    if let Some(path) = state_guard.held.take() {
+        release_file(&path, &state_guard.instance_id);
+        info!("[Lock] Released {:?}", path);
+    }
+}
+
+/// Move the sidecar to DataDirLocked and tell the frontend who holds the lock
+pub(crate) fn report_locked(app: &tauri::AppHandle, data_dir: &str, holder: LockInfo) {
+    info!(
+        "[Lock] {} is in use by {} (PID {})",
+        data_dir, holder.hostname, holder.pid
+    );
+    let data_dir = data_dir.to_string();
+    sidecar_status::transition(
+        app,
+        SidecarPhase::DataDirLocked {
+            holder: holder.clone(),
+        },
+        |state| {
+            state.data_dir = Some(data_dir);
+            state.next_restart_at = None;
+        },
+    );
+    let _ = app.emit("data-dir-locked", holder);
+}
+
+/// Refresh the heartbeat of a held lock; returns the new holder if it was taken over
+fn heartbeat(app: &tauri::AppHandle) -> Option<LockInfo> {
+    let state = app.state::<Mutex<DataDirLockState>>();
+    let mut state_guard = state.lock().unwrap();
+    let path = state_guard.held.clone()?;
+
+    match read_lock(&path) {
+        Some(lock) if lock.instance_id != state_guard.instance_id => {
+            state_guard.held = None;
+            Some(lock)
+        }
+        Some(mut lock) => {
+            lock.heartbeat_at = now_millis();
+            if let Err(e) = write_lock(&path, &lock) {
+                info!("[Lock] Heartbeat failed: {}", e);
+            }
+            None
+        }
+        // The lock file was deleted underneath us (e.g. by a sync tool); write it back
+        None => {
+            let now = now_millis();
+            let lock = LockInfo {
+                instance_id: state_guard.instance_id.clone(),
+                hostname: hostname(),
+                pid: std::process::id(),
+                app_version: app.package_info().version.to_string(),
+                acquired_at: now,
+                heartbeat_at: now,
+            };
+            if let Err(e) = write_lock(&path, &lock) {
+                info!("[Lock] Heartbeat failed: {}", e);
+            }
+            None
+        }
+    }
+}
+
+/// Lock held by a headless command (see cli.rs); removed when dropped
+pub(crate) struct HeadlessLock {
+    path: PathBuf,
+    info: LockInfo,
+}
+
+impl HeadlessLock {
+    /// Take the lock on `data_dir`; fails if a live instance holds it
+    pub(crate) fn acquire(data_dir: &Path) -> Result<HeadlessLock, LockError> {
+        std::fs::create_dir_all(data_dir)
+            .map_err(|e| LockError::Io(format!("Failed to create data directory: {}", e)))?;
+        let path = lock_path(data_dir);
+        if let Some(existing) = read_lock(&path).filter(is_live) {
+            return Err(LockError::Held(existing));
+        }
+
+        let now = now_millis();
+        let info = LockInfo {
+            instance_id: format!("cli-{}-{:x}", std::process::id(), now),
+            hostname: hostname(),
+            pid: std::process::id(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            acquired_at: now,
+            heartbeat_at: now,
+        };
+        write_lock(&path, &info).map_err(LockError::Io)?;
+        Ok(HeadlessLock { path, info })
+    }
+
+    /// Refresh the heartbeat; returns the new holder if the lock was taken over
+    pub(crate) fn heartbeat(&mut self) -> Option<LockInfo> {
+        if let Some(lock) = read_lock(&self.path) {
+            if lock.instance_id != self.info.instance_id {
+                return Some(lock);
+            }
+        }
+        self.info.heartbeat_at = now_millis();
+        if let Err(e) = write_lock(&self.path, &self.info) {
+            error!("[Lock] Heartbeat failed: {}", e);
+        }
+        None
+    }
+
+    /// Interval at which `heartbeat` must be called
+    pub(crate) fn interval() -> Duration {
+        HEARTBEAT_INTERVAL
+    }
+}
+
+impl Drop for HeadlessLock {
+    fn drop(&mut self) {
+        release_file(&self.path, &self.info.instance_id);
+    }
+}
+
+/// Keep the lock's heartbeat fresh and step aside when another instance takes over
+pub(crate) fn spawn_heartbeat(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(HEARTBEAT_INTERVAL).await;
+
+            let Some(holder) = heartbeat(&app) else {
+                continue;
+            };
+            let data_dir = {
+                let state = app.state::<Mutex<crate::SidecarState>>();
+                let state_guard = state.lock().unwrap();
+                state_guard.data_dir.clone().unwrap_or_default()
+            };
+            info!("[Lock] Data directory was taken over, stopping sidecar");
+            if let Err(e) = crate::shutdown::shutdown_sidecar(&app).await {
+                error!("[Lock] Failed to stop sidecar: {}", e);
+            }
+            report_locked(&app, &data_dir, holder);
+        }
+    });
+}
+
+/// Take over a data directory locked by another instance and start the sidecar
+#[tauri::command]
+pub(crate) async fn take_over_data_dir(app: tauri::AppHandle) -> Result<String, String> {
+    let data_dir = crate::snapshots::current_data_dir(&app)?;
+    acquire(&app, &data_dir, true).map_err(|e| match e {
+        LockError::Held(holder) => format!("Data directory is in use by {}", holder.hostname),
+        LockError::Io(e) => e,
+    })?;
+
+    crate::supervisor::reset(&app);
+    let result = crate::start_bun_sidecar_internal(&app, Some(data_dir)).await?;
+    tauri::async_runtime::spawn(async move {
+        let _ = crate::wait_for_sidecar_ready(&app).await;
+    });
+    Ok(result)
+}
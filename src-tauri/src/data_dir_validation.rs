@@ -0,0 +1,288 @@
+// Preflight checks for a candidate data directory.
+//
+// Run by the folder picker flow before a new location is committed, so the
+// user is warned about a folder the sidecar could not use (missing write
+// permission, no free space) or that needs care (a network share, a cloud
+// sync folder, a folder another instance is using) instead of getting an
+// opaque error from the next sidecar start.
+//
+// The result extends the sidecar's `DirectoryValidation`
+// (api/src/models/settings.ts) with the host-side checks.
+
+use std::path::{Path, PathBuf};
+
+use crate::data_dir_lock::LockInfo;
+use crate::snapshots::collect_data_files;
+
+/// Below this much free space the folder is reported with a warning
+const LOW_SPACE_BYTES: u64 = 100 * 1024 * 1024;
+
+/// File the sidecar keeps in DATA_DIR with the app version that last used it
+const VERSION_FILE: &str = "version.json";
+
+/// Filesystem types that live on another machine
+const NETWORK_FILESYSTEMS: [&str; 13] = [
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "afpfs",
+    "webdav",
+    "davfs",
+    "9p",
+    "afs",
+    "fuse.sshfs",
+    "fuse.rclone",
+    "fuse.gcsfuse",
+];
+
+/// Path fragments of folders managed by cloud sync clients, with the provider name
+const SYNC_PATH_MARKERS: [(&str, &str); 7] = [
+    ("Mobile Documents/com~apple~CloudDocs", "iCloud Drive"),
+    ("iCloud Drive", "iCloud Drive"),
+    ("Dropbox", "Dropbox"),
+    ("OneDrive", "OneDrive"),
+    ("Google Drive", "Google Drive"),
+    ("GoogleDrive", "Google Drive"),
+    ("Library/CloudStorage", "Cloud storage"),
+];
+
+/// Marker files that sync clients keep in the root of a synced folder
+const SYNC_ROOT_MARKERS: [(&str, &str); 3] = [
+    (".dropbox", "Dropbox"),
+    (".stfolder", "Syncthing"),
+    (".sync", "Resilio Sync"),
+];
+
+/// Result of `validate_data_directory`
+#[derive(Clone, Debug, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DirectoryValidation {
+    /// Whether the directory can be used
+    pub is_valid: bool,
+    pub exists: bool,
+    /// Whether a file could be created in the directory (or its nearest existing parent)
+    pub is_writable: bool,
+    /// Whether Doggy Bag data already exists at this location
+    pub has_existing_data: bool,
+    /// Existing data files, relative to the directory
+    pub existing_files: Vec<String>,
+    /// Whether the existing data is an encrypted vault
+    pub is_encrypted: bool,
+    /// App version recorded in version.json by the last sidecar that used the data
+    pub data_version: Option<String>,
+    /// Free space available to the user, if it could be determined
+    pub free_bytes: Option<u64>,
+    /// Filesystem type of the mount holding the directory
+    pub filesystem: Option<String>,
+    pub is_network_mount: bool,
+    /// Cloud sync client managing the folder, if recognized
+    pub sync_provider: Option<String>,
+    /// Another live instance holding the data directory lock
+    pub locked_by: Option<LockInfo>,
+    /// Problems that do not prevent using the directory
+    pub warnings: Vec<String>,
+    /// Why the directory cannot be used
+    pub error: Option<String>,
+}
+
+/// Closest ancestor of `path` (or `path` itself) that exists
+fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+    path.ancestors().find(|p| p.is_dir()).map(Path::to_path_buf)
+}
+
+/// Try to create and remove a file, which is more reliable than permission bits
+/// on network and sync mounts
+fn probe_writable(dir: &Path) -> bool {
+    let probe = dir.join(format!(".doggybag-write-test-{}", std::process::id()));
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(unix)]
+fn free_space(dir: &Path) -> Option<u64> {
+    let stat = nix::sys::statvfs::statvfs(dir).ok()?;
+    Some(stat.blocks_available() as u64 * stat.fragment_size() as u64)
+}
+
+#[cfg(not(unix))]
+fn free_space(_dir: &Path) -> Option<u64> {
+    None
+}
+
+/// Filesystem type from the mount table entry with the longest matching mount point
+#[cfg(target_os = "linux")]
+fn filesystem_type(dir: &Path) -> Option<String> {
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+    let dir = dir.canonicalize().ok()?;
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            // Spaces in mount points are escaped as \040
+            let mount_point = fields.next()?.replace("\\040", " ");
+            let fs_type = fields.next()?;
+            dir.starts_with(&mount_point)
+                .then(|| (mount_point.len(), fs_type.to_string()))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, fs_type)| fs_type)
+}
+
+#[cfg(target_os = "macos")]
+fn filesystem_type(dir: &Path) -> Option<String> {
+    let stat = nix::sys::statfs::statfs(dir).ok()?;
+    Some(stat.filesystem_type_name().to_string())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn filesystem_type(_dir: &Path) -> Option<String> {
+    None
+}
+
+/// Cloud sync client managing `path`, from well-known folder names or root markers
+fn sync_provider(path: &Path) -> Option<String> {
+    let text = path.to_string_lossy().replace('\\', "/");
+    for (marker, provider) in SYNC_PATH_MARKERS {
+        if text.contains(marker) {
+            return Some(provider.to_string());
+        }
+    }
+    for dir in path.ancestors() {
+        for (marker, provider) in SYNC_ROOT_MARKERS {
+            if dir.join(marker).exists() {
+                return Some(provider.to_string());
+            }
+        }
+    }
+    None
+}
+
+fn read_data_version(path: &Path) -> Option<String> {
+    let content = std::fs::read(path.join(VERSION_FILE)).ok()?;
+    let json: serde_json::Value = serde_json::from_slice(&content).ok()?;
+    json.get("version")?.as_str().map(|v| v.to_string())
+}
+
+/// Numeric components of a version like "0.11.1", for ordering
+fn version_parts(version: &str) -> Vec<u64> {
+    version
+        .split(['.', '-'])
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+fn format_bytes(bytes: u64) -> String {
+    format!("{:.0} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+/// Check whether `path` can be used as the data directory
+pub(crate) fn validate(app: &tauri::AppHandle, path: &str) -> DirectoryValidation {
+    let mut result = DirectoryValidation::default();
+    if path.trim().is_empty() {
+        result.error = Some("Invalid path: path is required".to_string());
+        return result;
+    }
+    let dir = PathBuf::from(path);
+    if !dir.is_absolute() {
+        result.error = Some("Invalid path: must be an absolute path".to_string());
+        return result;
+    }
+
+    result.exists = dir.is_dir();
+    if dir.exists() && !result.exists {
+        result.error = Some("Path exists but is not a directory".to_string());
+        return result;
+    }
+
+    // A missing directory is created on use, so check the parent it would be created in
+    let Some(base) = existing_ancestor(&dir) else {
+        result.error = Some("No parent directory of this path exists".to_string());
+        return result;
+    };
+    result.is_writable = probe_writable(&base);
+    if !result.is_writable {
+        result.error = Some(if result.exists {
+            "Permission denied: directory is not writable".to_string()
+        } else {
+            "Cannot create directory: parent directory is not writable".to_string()
+        });
+    }
+
+    result.free_bytes = free_space(&base);
+    if let Some(free) = result.free_bytes {
+        if free < LOW_SPACE_BYTES {
+            result
+                .warnings
+                .push(format!("Only {} of free space left", format_bytes(free)));
+        }
+    }
+
+    result.filesystem = filesystem_type(&base);
+    result.is_network_mount = result
+        .filesystem
+        .as_deref()
+        .is_some_and(|fs| NETWORK_FILESYSTEMS.contains(&fs));
+    if result.is_network_mount {
+        result.warnings.push(
+            "This folder is on a network drive; the app stops working when it is disconnected"
+                .to_string(),
+        );
+    }
+
+    result.sync_provider = sync_provider(&dir);
+    if let Some(provider) = &result.sync_provider {
+        result.warnings.push(format!(
+            "This folder is synced by {}; only use it on one computer at a time",
+            provider
+        ));
+    }
+
+    if result.exists {
+        result.existing_files = collect_data_files(&dir)
+            .into_iter()
+            .map(|(_, rel)| rel)
+            .collect();
+        result.is_encrypted = crate::vault::is_vault(&dir);
+        result.has_existing_data = !result.existing_files.is_empty() || result.is_encrypted;
+        result.data_version = read_data_version(&dir);
+        if let Some(version) = &result.data_version {
+            let app_version = app.package_info().version.to_string();
+            if version_parts(version) > version_parts(&app_version) {
+                result.warnings.push(format!(
+                    "This data was last used by a newer version ({}) of the app",
+                    version
+                ));
+            }
+        }
+
+        result.locked_by = crate::data_dir_lock::current_holder(app, &dir);
+        if let Some(holder) = &result.locked_by {
+            result.warnings.push(format!(
+                "This folder is in use by Doggy Bag on {} (PID {})",
+                holder.hostname, holder.pid
+            ));
+        }
+    }
+
+    result.is_valid = result.error.is_none();
+    result
+}
+
+/// Check a candidate data directory before switching to it
+#[tauri::command]
+pub(crate) async fn validate_data_directory(
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<DirectoryValidation, String> {
+    tauri::async_runtime::spawn_blocking(move || validate(&app, &path))
+        .await
+        .map_err(|e| format!("Validation task failed: {}", e))
+}
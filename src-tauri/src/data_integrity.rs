@@ -0,0 +1,235 @@
+// Data directory integrity check.
+//
+// Everything in DATA_DIR is plain JSON that a crash or a cloud-sync conflict
+// can leave truncated. Every time the sidecar starts, the host parses every
+// file in `entities/` and `months/` of the directory the sidecar runs on (the
+// decrypted working copy of a vault) and refuses to start a backend that would
+// fail on them later. The same check is available to the frontend through the
+// `verify_data_directory` command. A failed check names the newest snapshot
+// with a good copy of every damaged file, so the frontend can offer to
+// restore them.
+
+use std::path::Path;
+
+/// Subdirectories that hold the budget data, with the top-level JSON type
+/// every file in them must have
+const CHECKED_DIRS: [(&str, JsonShape); 2] = [
+    ("entities", JsonShape::Array),
+    ("months", JsonShape::Object),
+];
+
+#[derive(Clone, Copy)]
+enum JsonShape {
+    Array,
+    Object,
+}
+
+impl JsonShape {
+    fn matches(self, value: &serde_json::Value) -> bool {
+        match self {
+            JsonShape::Array => value.is_array(),
+            JsonShape::Object => value.is_object(),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            JsonShape::Array => "array",
+            JsonShape::Object => "object",
+        }
+    }
+}
+
+/// Result of checking a single data file
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub(crate) enum FileStatus {
+    Ok,
+    /// Zero-byte (or whitespace-only) file
+    Empty,
+    /// File is not valid JSON
+    Corrupt {
+        error: String,
+    },
+    /// Valid JSON, but not the top-level type this directory holds
+    UnexpectedShape {
+        expected: String,
+    },
+    /// File could not be read at all
+    Unreadable {
+        error: String,
+    },
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FileReport {
+    /// Path relative to the data directory, e.g. `months/2026-01.json`
+    pub path: String,
+    pub size: u64,
+    #[serde(flatten)]
+    pub status: FileStatus,
+}
+
+/// Per-file report returned by `verify_data_directory`
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataDirectoryReport {
+    pub data_dir: String,
+    pub checked_at: u64,
+    pub files: Vec<FileReport>,
+    /// Number of files whose status is not Ok
+    pub problems: usize,
+    /// Newest snapshot of this data directory holding a valid copy of every
+    /// damaged file (None when healthy or when no snapshot has them)
+    pub restore_snapshot: Option<String>,
+}
+
+impl DataDirectoryReport {
+    pub fn is_healthy(&self) -> bool {
+        self.problems == 0
+    }
+
+    /// Paths of the files whose status is not Ok
+    pub fn damaged_files(&self) -> Vec<String> {
+        self.files
+            .iter()
+            .filter(|f| !matches!(f.status, FileStatus::Ok))
+            .map(|f| f.path.clone())
+            .collect()
+    }
+
+    /// Look up the snapshot a damaged data directory can be restored from
+    pub fn find_restore_snapshot(&mut self, app: &tauri::AppHandle) {
+        if self.is_healthy() {
+            return;
+        }
+        self.restore_snapshot = crate::snapshots::newest_snapshot_with(
+            app,
+            &self.data_dir,
+            &self.damaged_files(),
+            is_valid_content,
+        );
+    }
+
+    /// Short human-readable list of the problem files (used as a Failed reason)
+    pub fn summary(&self) -> String {
+        let files: Vec<String> = self
+            .files
+            .iter()
+            .filter_map(|f| match &f.status {
+                FileStatus::Ok => None,
+                FileStatus::Empty => Some(format!("{} (empty)", f.path)),
+                FileStatus::Corrupt { .. } => Some(format!("{} (invalid JSON)", f.path)),
+                FileStatus::UnexpectedShape { expected } => {
+                    Some(format!("{} (expected {})", f.path, expected))
+                }
+                FileStatus::Unreadable { .. } => Some(format!("{} (unreadable)", f.path)),
+            })
+            .collect();
+        format!("Damaged data files: {}", files.join(", "))
+    }
+}
+
+fn check_content(content: &[u8], shape: JsonShape) -> FileStatus {
+    if content.iter().all(|b| b.is_ascii_whitespace()) {
+        return FileStatus::Empty;
+    }
+
+    match serde_json::from_slice::<serde_json::Value>(content) {
+        Ok(value) if shape.matches(&value) => FileStatus::Ok,
+        Ok(_) => FileStatus::UnexpectedShape {
+            expected: shape.name().to_string(),
+        },
+        Err(e) => FileStatus::Corrupt {
+            error: e.to_string(),
+        },
+    }
+}
+
+fn check_file(path: &Path, shape: JsonShape) -> (u64, FileStatus) {
+    let content = match std::fs::read(path) {
+        Ok(c) => c,
+        Err(e) => {
+            return (
+                0,
+                FileStatus::Unreadable {
+                    error: e.to_string(),
+                },
+            )
+        }
+    };
+    (content.len() as u64, check_content(&content, shape))
+}
+
+/// Whether `content` would pass the check as the data file `rel` (e.g. `months/2026-01.json`)
+fn is_valid_content(rel: &str, content: &[u8]) -> bool {
+    CHECKED_DIRS
+        .iter()
+        .find(|(subdir, _)| rel.starts_with(&format!("{}/", subdir)))
+        .is_some_and(|(_, shape)| matches!(check_content(content, *shape), FileStatus::Ok))
+}
+
+/// Parse every JSON file in `entities/` and `months/` under `data_dir`
+/// Missing subdirectories are not an error (a fresh data directory has none yet)
+pub(crate) fn verify_data_dir(data_dir: &Path) -> DataDirectoryReport {
+    let mut files = Vec::new();
+
+    for (subdir, shape) in CHECKED_DIRS {
+        let dir = data_dir.join(subdir);
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let (size, status) = check_file(&path, shape);
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            files.push(FileReport {
+                path: format!("{}/{}", subdir, name),
+                size,
+                status,
+            });
+        }
+    }
+
+    let problems = files
+        .iter()
+        .filter(|f| !matches!(f.status, FileStatus::Ok))
+        .count();
+
+    DataDirectoryReport {
+        data_dir: data_dir.to_string_lossy().to_string(),
+        checked_at: crate::sidecar_status::now_millis(),
+        files,
+        problems,
+        restore_snapshot: None,
+    }
+}
+
+/// Verify every data file in a data directory
+/// Defaults to the saved data directory (or ~/Documents/DoggyBag/) when no path is given
+#[tauri::command]
+pub(crate) async fn verify_data_directory(
+    app: tauri::AppHandle,
+    data_dir: Option<String>,
+) -> Result<DataDirectoryReport, String> {
+    let data_dir = crate::resolve_data_dir(data_dir.or_else(|| crate::read_saved_data_dir(&app)))?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut report = verify_data_dir(Path::new(&data_dir));
+        report.find_restore_snapshot(&app);
+        report
+    })
+    .await
+    .map_err(|e| format!("Data directory check failed: {}", e))
+}
@@ -0,0 +1,569 @@
+// Moving the data directory to a new location.
+//
+// The sidecar is stopped for the whole migration so nothing writes to the
+// source while it is copied. Every data file (entities/, months/,
+// data/documents/, backups/ and version.json, plus the vault header of an
+// encrypted directory) is copied with a SHA-256 checksum, then read back from the destination and compared.
+// Only after every file verified is `dataDirectory` switched in settings.json
+// and the sidecar started on the new path; any failure before that point
+// removes the partial copy and starts the sidecar on the source again, so data
+// is never split across two folders.
+//
+// Modes:
+// - copy: copy the data, keep the source
+// - move: copy the data, then delete it from the source once the sidecar is
+//   ready on the destination (the source is kept when it does not get there,
+//   or when the destination is a locked vault no backend has read yet)
+// - adopt-existing: switch to a destination that already holds data (e.g. a
+//   synced folder from another machine) without copying anything
+//
+// Progress is reported with `data-migration-progress` events.
+
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+use tracing::{error, info};
+
+use crate::snapshots::{SnapshotReason, SNAPSHOT_DIRS};
+
+/// What to do with the data in the source and destination directories
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum MigrationMode {
+    Copy,
+    Move,
+    AdoptExisting,
+}
+
+/// Migration step reported in progress events
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum MigrationPhase {
+    Preparing,
+    Copying,
+    Verifying,
+    Switching,
+    RemovingSource,
+    Done,
+}
+
+/// Payload of the `data-migration-progress` event
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MigrationProgress {
+    pub phase: MigrationPhase,
+    /// Files processed in this phase so far
+    pub current: usize,
+    pub total: usize,
+    /// Relative path of the file being processed
+    pub file: Option<String>,
+}
+
+/// Result returned by `migrate_data_directory`
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MigrationReport {
+    pub mode: MigrationMode,
+    pub source_dir: String,
+    pub dest_dir: String,
+    pub entity_files_copied: usize,
+    pub month_files_copied: usize,
+    pub document_files_copied: usize,
+    pub bytes_copied: u64,
+    /// Whether the source data was deleted (move mode, once the backend was
+    /// ready on the destination)
+    pub source_removed: bool,
+}
+
+/// A copied file and the checksum of its source content
+struct CopiedFile {
+    rel: String,
+    sha256: String,
+}
+
+fn emit_progress(
+    app: &tauri::AppHandle,
+    phase: MigrationPhase,
+    current: usize,
+    total: usize,
+    file: Option<&str>,
+) {
+    let _ = app.emit(
+        "data-migration-progress",
+        MigrationProgress {
+            phase,
+            current,
+            total,
+            file: file.map(|f| f.to_string()),
+        },
+    );
+}
+
+/// Data files to migrate, the same set for plain and encrypted directories
+/// (see vault::data_dir_files) plus the vault header
+fn migration_files(data_dir: &Path) -> Vec<(PathBuf, String)> {
+    let mut files = crate::vault::data_dir_files(data_dir);
+    let header = data_dir.join(crate::vault::HEADER_FILE);
+    if header.is_file() {
+        files.push((header, crate::vault::HEADER_FILE.to_string()));
+    }
+    files
+}
+
+/// Absolute form of `path` with symlinks resolved as far as it exists
+fn normalize(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => normalize(parent).join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// Copy every file to `dest`, writing through a temp file and renaming it into place
+/// Files already copied are pushed to `copied` even when a later file fails.
+fn copy_files(
+    files: &[(PathBuf, String)],
+    dest: &Path,
+    copied: &mut Vec<CopiedFile>,
+    progress: &impl Fn(MigrationPhase, usize, usize, Option<&str>),
+) -> Result<u64, String> {
+    let mut bytes = 0u64;
+    for (index, (path, rel)) in files.iter().enumerate() {
+        progress(MigrationPhase::Copying, index, files.len(), Some(rel));
+
+        let content = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+        let target = dest.join(rel);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let temp_path = PathBuf::from(format!("{}.migrating", target.to_string_lossy()));
+        std::fs::write(&temp_path, &content)
+            .and_then(|_| std::fs::rename(&temp_path, &target))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&temp_path);
+                format!("Failed to write {}: {}", rel, e)
+            })?;
+
+        copied.push(CopiedFile {
+            rel: rel.clone(),
+            sha256: sha256_hex(&content),
+        });
+        bytes += content.len() as u64;
+    }
+    progress(MigrationPhase::Copying, files.len(), files.len(), None);
+    Ok(bytes)
+}
+
+/// Read every copied file back from `dest` and compare it with the source checksum
+fn verify_files(
+    copied: &[CopiedFile],
+    dest: &Path,
+    progress: &impl Fn(MigrationPhase, usize, usize, Option<&str>),
+) -> Result<(), String> {
+    for (index, file) in copied.iter().enumerate() {
+        progress(
+            MigrationPhase::Verifying,
+            index,
+            copied.len(),
+            Some(&file.rel),
+        );
+        let content = std::fs::read(dest.join(&file.rel))
+            .map_err(|e| format!("Failed to read back {}: {}", file.rel, e))?;
+        if sha256_hex(&content) != file.sha256 {
+            return Err(format!("Checksum mismatch for {} after copying", file.rel));
+        }
+    }
+    progress(MigrationPhase::Verifying, copied.len(), copied.len(), None);
+    Ok(())
+}
+
+/// Delete files written to the destination by a failed migration
+fn remove_copied(copied: &[CopiedFile], dest: &Path) {
+    for file in copied {
+        if let Err(e) = std::fs::remove_file(dest.join(&file.rel)) {
+            error!(
+                "[Migration] Failed to remove partial copy {}: {}",
+                file.rel, e
+            );
+        }
+    }
+}
+
+/// Delete the migrated files from the source and prune directories left empty
+fn remove_source(
+    app: &tauri::AppHandle,
+    files: &[(PathBuf, String)],
+    source: &Path,
+) -> Result<(), String> {
+    for (index, (path, rel)) in files.iter().enumerate() {
+        emit_progress(
+            app,
+            MigrationPhase::RemovingSource,
+            index,
+            files.len(),
+            Some(rel),
+        );
+        std::fs::remove_file(path).map_err(|e| format!("Failed to remove {}: {}", rel, e))?;
+    }
+
+    // remove_dir only succeeds on empty directories, so anything else is kept
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for (path, _) in files {
+        let mut dir = path.parent();
+        while let Some(d) = dir {
+            if d == source || !d.starts_with(source) {
+                break;
+            }
+            dirs.push(d.to_path_buf());
+            dir = d.parent();
+        }
+    }
+    for subdir in SNAPSHOT_DIRS {
+        dirs.push(source.join(subdir));
+    }
+    // Deepest first so parents are empty by the time they are reached
+    dirs.sort_by(|a, b| {
+        b.components()
+            .count()
+            .cmp(&a.components().count())
+            .then_with(|| a.cmp(b))
+    });
+    dirs.dedup();
+    for dir in dirs {
+        let _ = std::fs::remove_dir(dir);
+    }
+    Ok(())
+}
+
+/// Start the sidecar on `data_dir` and track readiness in the background
+async fn start_sidecar(app: &tauri::AppHandle, data_dir: String) -> Result<String, String> {
+    crate::supervisor::reset(app);
+    let result = crate::start_bun_sidecar_internal(app, Some(data_dir)).await?;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let _ = crate::wait_for_sidecar_ready(&app).await;
+    });
+    Ok(result)
+}
+
+/// Undo a failed migration: drop the partial copy and go back to the source
+async fn roll_back(
+    app: &tauri::AppHandle,
+    source_dir: &str,
+    dest: PathBuf,
+    copied: Vec<CopiedFile>,
+    error: String,
+) -> Result<MigrationReport, String> {
+    error!("[Migration] {}, rolling back", error);
+    let _ = tauri::async_runtime::spawn_blocking(move || remove_copied(&copied, &dest)).await;
+    if let Err(e) = start_sidecar(app, source_dir.to_string()).await {
+        error!("[Migration] Failed to restart sidecar on the source: {}", e);
+    }
+    Err(error)
+}
+
+/// Move the data directory to `dest` and restart the sidecar there
+/// `src` is the current data directory; see the module comment for `mode`.
+#[tauri::command]
+pub(crate) async fn migrate_data_directory(
+    app: tauri::AppHandle,
+    src: String,
+    dest: String,
+    mode: MigrationMode,
+) -> Result<MigrationReport, String> {
+    let source_dir = crate::resolve_data_dir(Some(src))?;
+    if dest.trim().is_empty() {
+        return Err("Destination directory is required".to_string());
+    }
+    let source = normalize(Path::new(&source_dir));
+    let dest_path = normalize(Path::new(&dest));
+    if !dest_path.is_absolute() {
+        return Err("Destination directory must be an absolute path".to_string());
+    }
+    if source == dest_path {
+        return Err("The destination is already the data directory".to_string());
+    }
+    if dest_path.starts_with(&source) || source.starts_with(&dest_path) {
+        return Err("The source and destination directories cannot contain each other".to_string());
+    }
+
+    let dest_has_data = !migration_files(&dest_path).is_empty();
+    match mode {
+        MigrationMode::Copy | MigrationMode::Move if dest_has_data => {
+            return Err(
+                "The destination already contains data; use it as-is or choose an empty folder"
+                    .to_string(),
+            );
+        }
+        MigrationMode::AdoptExisting if !dest_has_data => {
+            return Err("No existing data found at the destination".to_string());
+        }
+        MigrationMode::AdoptExisting if !crate::vault::is_vault(&dest_path) => {
+            let report = crate::data_integrity::verify_data_dir(&dest_path);
+            if !report.is_healthy() {
+                return Err(format!(
+                    "The data at the destination is damaged: {}",
+                    report.summary()
+                ));
+            }
+        }
+        _ => {}
+    }
+
+    info!(
+        "[Migration] {:?}: {} -> {}",
+        mode,
+        source_dir,
+        dest_path.display()
+    );
+    emit_progress(&app, MigrationPhase::Preparing, 0, 0, None);
+    let dest_dir = dest_path.to_string_lossy().to_string();
+
+    if mode == MigrationMode::Move {
+        crate::snapshots::take_snapshot(&app, source_dir.clone(), SnapshotReason::PreMigrate)
+            .await?;
+    }
+
+    // Nothing may write to the source while it is copied
+    crate::shutdown::shutdown_sidecar(&app).await?;
+    crate::vault::close(&app);
+
+    // Lock the destination now so no other instance starts using it mid-copy
+    // (this also releases the lock on the source)
+    if let Err(e) = crate::data_dir_lock::acquire(&app, &dest_dir, false) {
+        let error = match e {
+            crate::data_dir_lock::LockError::Held(holder) => format!(
+                "The destination is in use by {} (PID {})",
+                holder.hostname, holder.pid
+            ),
+            crate::data_dir_lock::LockError::Io(e) => e,
+        };
+        return roll_back(&app, &source_dir, dest_path, Vec::new(), error).await;
+    }
+
+    let files = if mode == MigrationMode::AdoptExisting {
+        Vec::new()
+    } else {
+        migration_files(&source)
+    };
+
+    let mut copied = Vec::new();
+    let mut bytes_copied = 0;
+    if !files.is_empty() {
+        let handle = app.clone();
+        let target = dest_path.clone();
+        let job_files = files.clone();
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            let progress = |phase, current, total, file: Option<&str>| {
+                emit_progress(&handle, phase, current, total, file)
+            };
+            let mut copied = Vec::new();
+            let result = copy_files(&job_files, &target, &mut copied, &progress)
+                .and_then(|bytes| verify_files(&copied, &target, &progress).map(|_| bytes));
+            (copied, result)
+        })
+        .await;
+        let (done, result) = match result {
+            Ok(outcome) => outcome,
+            Err(e) => (Vec::new(), Err(format!("Copy task failed: {}", e))),
+        };
+        copied = done;
+        match result {
+            Ok(bytes) => bytes_copied = bytes,
+            Err(e) => return roll_back(&app, &source_dir, dest_path, copied, e).await,
+        }
+    }
+
+    emit_progress(&app, MigrationPhase::Switching, 0, 1, None);
+    if let Err(e) = crate::write_setting(&app, "dataDirectory", serde_json::json!(dest_dir)) {
+        return roll_back(&app, &source_dir, dest_path, copied, e).await;
+    }
+    crate::vault::rebind(&app, &source, &dest_path).await;
+
+    // Wait for the backend, so the source is only deleted once it has read the copy
+    crate::supervisor::reset(&app);
+    let ready = match crate::start_bun_sidecar_internal(&app, Some(dest_dir.clone())).await {
+        Ok(_) => match crate::wait_for_sidecar_ready(&app).await {
+            Ok(_) => true,
+            Err(e) => {
+                error!(
+                    "[Migration] Sidecar did not become ready on the destination: {}",
+                    e
+                );
+                false
+            }
+        },
+        Err(e) => {
+            // A locked vault is not a failure: the lock screen asks for the passphrase
+            let locked_vault =
+                crate::vault::is_vault(&dest_path) && !crate::vault::is_unlocked(&app, &dest_path);
+            if !locked_vault {
+                let _ = crate::write_setting(&app, "dataDirectory", serde_json::json!(source_dir));
+                crate::vault::rebind(&app, &dest_path, &source).await;
+                return roll_back(&app, &source_dir, dest_path, copied, e).await;
+            }
+            false
+        }
+    };
+
+    let mut source_removed = false;
+    if mode == MigrationMode::Move && !ready {
+        info!("[Migration] Keeping the source data until the destination has been opened");
+    } else if mode == MigrationMode::Move {
+        let handle = app.clone();
+        let root = source.clone();
+        let job_files = files.clone();
+        match tauri::async_runtime::spawn_blocking(move || {
+            remove_source(&handle, &job_files, &root)
+        })
+        .await
+        {
+            Ok(Ok(())) => source_removed = true,
+            Ok(Err(e)) => error!("[Migration] Failed to remove the source data: {}", e),
+            Err(e) => error!("[Migration] Cleanup task failed: {}", e),
+        }
+    }
+
+    let count = |prefix: &str| copied.iter().filter(|f| f.rel.starts_with(prefix)).count();
+    let report = MigrationReport {
+        mode,
+        source_dir,
+        dest_dir,
+        entity_files_copied: count("entities/"),
+        month_files_copied: count("months/"),
+        document_files_copied: count("data/documents/"),
+        bytes_copied,
+        source_removed,
+    };
+    emit_progress(&app, MigrationPhase::Done, 1, 1, None);
+    info!(
+        "[Migration] Done: {} files ({} bytes) copied, source removed: {}",
+        copied.len(),
+        bytes_copied,
+        source_removed
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_progress(_: MigrationPhase, _: usize, _: usize, _: Option<&str>) {}
+
+    /// A plain data directory with a file in every place the backend writes
+    fn plain_data_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (rel, content) in [
+            ("entities/bills.json", "[]"),
+            ("months/2026-01.json", "{}"),
+            ("data/documents/receipt.pdf", "%PDF"),
+            ("backups/entities-1.json", "[]"),
+            ("version.json", "{\"version\":1}"),
+        ] {
+            let path = dir.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    fn rels(files: &[(PathBuf, String)]) -> Vec<&str> {
+        files.iter().map(|(_, rel)| rel.as_str()).collect()
+    }
+
+    #[test]
+    fn migration_files_covers_backups_and_version_in_plain_directories() {
+        let source = plain_data_dir();
+        assert_eq!(
+            rels(&migration_files(source.path())),
+            [
+                "backups/entities-1.json",
+                "data/documents/receipt.pdf",
+                "entities/bills.json",
+                "months/2026-01.json",
+                "version.json",
+            ]
+        );
+    }
+
+    #[test]
+    fn migration_files_covers_the_same_files_in_a_vault() {
+        let source = tempfile::tempdir().unwrap();
+        for rel in [
+            "entities/bills.json.enc",
+            "backups/entities-1.json.enc",
+            "version.json.enc",
+            crate::vault::HEADER_FILE,
+        ] {
+            let path = source.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "x").unwrap();
+        }
+        assert_eq!(
+            rels(&migration_files(source.path())),
+            [
+                "backups/entities-1.json.enc",
+                "entities/bills.json.enc",
+                "version.json.enc",
+                crate::vault::HEADER_FILE,
+            ]
+        );
+    }
+
+    #[test]
+    fn copy_and_verify_reproduce_every_file() {
+        let source = plain_data_dir();
+        let dest = tempfile::tempdir().unwrap();
+        let files = migration_files(source.path());
+
+        let mut copied = Vec::new();
+        let bytes = copy_files(&files, dest.path(), &mut copied, &no_progress).unwrap();
+        verify_files(&copied, dest.path(), &no_progress).unwrap();
+
+        assert_eq!(copied.len(), files.len());
+        assert_eq!(bytes, 2 + 2 + 4 + 2 + 13);
+        for (path, rel) in &files {
+            assert_eq!(
+                std::fs::read(dest.path().join(rel)).unwrap(),
+                std::fs::read(path).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn failed_verification_rolls_back_and_leaves_the_source_untouched() {
+        let source = plain_data_dir();
+        let dest = tempfile::tempdir().unwrap();
+        let files = migration_files(source.path());
+        let before: Vec<Vec<u8>> = files
+            .iter()
+            .map(|(path, _)| std::fs::read(path).unwrap())
+            .collect();
+
+        let mut copied = Vec::new();
+        copy_files(&files, dest.path(), &mut copied, &no_progress).unwrap();
+        std::fs::write(dest.path().join("months/2026-01.json"), "{\"torn\":").unwrap();
+
+        let error = verify_files(&copied, dest.path(), &no_progress).unwrap_err();
+        assert_eq!(
+            error,
+            "Checksum mismatch for months/2026-01.json after copying"
+        );
+
+        remove_copied(&copied, dest.path());
+        assert!(migration_files(dest.path()).is_empty());
+        let after: Vec<Vec<u8>> = files
+            .iter()
+            .map(|(path, _)| std::fs::read(path).unwrap())
+            .collect();
+        assert_eq!(after, before);
+    }
+}
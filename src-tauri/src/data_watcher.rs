@@ -0,0 +1,277 @@
+// Watches DATA_DIR for changes made outside the app.
+//
+// When the data directory lives on a synced drive, Dropbox or iCloud can
+// rewrite `months/2026-01.json` while the app is running. The watcher
+// collects change events for the JSON files in `entities/` and `months/`,
+// waits until they settle and emits `data-changed-externally` so the
+// frontend can reload those stores instead of overwriting the other
+// device's changes.
+//
+// The sidecar's own writes are not reported. The backend writes every file
+// through `<file>.tmp-<pid>` and renames it into place (see
+// api/src/services/storage.ts), so a temp file event marks the target as
+// written by the sidecar for a short window. New sync conflict copies are
+// reported separately through `sync-conflicts-detected`.
+//
+// A vault (see vault.rs) only holds `<file>.enc` files while the sidecar runs
+// on a decrypted working copy. There the watcher follows the encrypted files,
+// decrypts the changed ones into the working copy and reports those. The host
+// writes the vault through `<file>.enc.vault-tmp`, which marks its own writes.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+use tracing::{error, info};
+
+use crate::{sync_conflicts, vault};
+
+/// Quiet period after the last change before the batch is emitted
+const DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// Upper bound on how long a steady stream of changes delays the event
+const MAX_BATCH_DELAY: Duration = Duration::from_secs(5);
+
+/// How long a sidecar temp file marks its target as self-written
+const SELF_WRITE_WINDOW: Duration = Duration::from_secs(2);
+
+/// Subdirectories whose JSON files are reported
+const WATCHED_DIRS: [&str; 2] = ["entities", "months"];
+
+/// The active watcher (dropping it stops the watch thread)
+#[derive(Default)]
+pub(crate) struct DataWatcherState {
+    data_dir: Option<PathBuf>,
+    /// Whether the directory was a vault when the watch started
+    vault: bool,
+    watcher: Option<notify::RecommendedWatcher>,
+}
+
+/// Payload of the `data-changed-externally` event
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ExternalChange {
+    pub data_dir: String,
+    /// Paths relative to the data directory, e.g. `months/2026-01.json`
+    pub files: Vec<String>,
+    /// Entity names, e.g. `bills` for `entities/bills.json`
+    pub entities: Vec<String>,
+    /// Months, e.g. `2026-01` for `months/2026-01.json`
+    pub months: Vec<String>,
+}
+
+/// A data file an event refers to
+enum Touched {
+    /// A sidecar temp file for the given target path
+    SelfWrite(String),
+    /// A data file, as a path relative to the data directory
+    DataFile(String),
+}
+
+/// Classify an event path, ignoring anything outside entities/ and months/
+/// In a vault the encrypted files are reported by their plaintext path.
+fn classify(data_dir: &Path, path: &Path, vault: bool) -> Option<Touched> {
+    let rel = path.strip_prefix(data_dir).ok()?;
+    let mut components = rel.components();
+    let dir = components.next()?.as_os_str().to_str()?;
+    let name = components.next()?.as_os_str().to_str()?;
+    if components.next().is_some() || !WATCHED_DIRS.contains(&dir) {
+        return None;
+    }
+
+    if vault {
+        let (name, own) = match name.strip_suffix(".vault-tmp") {
+            Some(target) => (target, true),
+            None => (name, false),
+        };
+        let target = name.strip_suffix(vault::ENCRYPTED_SUFFIX)?;
+        if !target.ends_with(".json") {
+            return None;
+        }
+        let file = format!("{}/{}", dir, target);
+        return Some(if own {
+            Touched::SelfWrite(file)
+        } else {
+            Touched::DataFile(file)
+        });
+    }
+
+    if let Some((target, pid)) = name.rsplit_once(".tmp-") {
+        if target.ends_with(".json") && pid.chars().all(|c| c.is_ascii_digit()) {
+            return Some(Touched::SelfWrite(format!("{}/{}", dir, target)));
+        }
+        return None;
+    }
+
+    if name.ends_with(".json") {
+        return Some(Touched::DataFile(format!("{}/{}", dir, name)));
+    }
+    None
+}
+
+fn build_change(data_dir: &Path, files: BTreeSet<String>) -> ExternalChange {
+    let mut entities = Vec::new();
+    let mut months = Vec::new();
+    for file in &files {
+        let stem = |prefix: &str| {
+            file.strip_prefix(prefix)
+                .and_then(|f| f.strip_suffix(".json"))
+                .map(|s| s.to_string())
+        };
+        if let Some(entity) = stem("entities/") {
+            entities.push(entity);
+        } else if let Some(month) = stem("months/") {
+            months.push(month);
+        }
+    }
+
+    ExternalChange {
+        data_dir: data_dir.to_string_lossy().to_string(),
+        files: files.into_iter().collect(),
+        entities,
+        months,
+    }
+}
+
+/// Collect events, drop the sidecar's own writes and emit settled batches
+/// `vault_dir` is the data directory as the vault knows it when it is a vault.
+fn run_debouncer(
+    app: tauri::AppHandle,
+    data_dir: PathBuf,
+    vault_dir: Option<PathBuf>,
+    rx: mpsc::Receiver<notify::Result<notify::Event>>,
+) {
+    let mut self_writes: HashMap<String, Instant> = HashMap::new();
+    let mut pending: BTreeSet<String> = BTreeSet::new();
+    let mut batch_started: Option<Instant> = None;
+    let mut last_event = Instant::now();
+
+    loop {
+        let timeout = if pending.is_empty() {
+            Duration::from_secs(60)
+        } else {
+            DEBOUNCE.saturating_sub(last_event.elapsed())
+        };
+
+        match rx.recv_timeout(timeout) {
+            Ok(Ok(event)) => {
+                if event.kind.is_access() {
+                    continue;
+                }
+                let now = Instant::now();
+                for path in &event.paths {
+                    match classify(&data_dir, path, vault_dir.is_some()) {
+                        Some(Touched::SelfWrite(target)) => {
+                            self_writes.insert(target.clone(), now);
+                            pending.remove(&target);
+                        }
+                        Some(Touched::DataFile(file)) => {
+                            let own_write = self_writes
+                                .get(&file)
+                                .is_some_and(|at| now.duration_since(*at) < SELF_WRITE_WINDOW);
+                            if !own_write {
+                                pending.insert(file);
+                                batch_started.get_or_insert(now);
+                                last_event = now;
+                            }
+                        }
+                        None => {}
+                    }
+                }
+                self_writes.retain(|_, at| now.duration_since(*at) < SELF_WRITE_WINDOW);
+
+                // Keep collecting unless the batch has been held back long enough
+                if batch_started.is_none_or(|start| start.elapsed() < MAX_BATCH_DELAY) {
+                    continue;
+                }
+            }
+            Ok(Err(e)) => {
+                info!("[Watcher] Watch error: {}", e);
+                continue;
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            // The watcher was dropped (data directory changed or app exiting)
+            Err(mpsc::RecvTimeoutError::Disconnected) => return,
+        }
+
+        if pending.is_empty() {
+            continue;
+        }
+        batch_started = None;
+
+        // Conflict copies are not data the backend reads; report them separately
+        let (copies, changed): (BTreeSet<String>, BTreeSet<String>) = std::mem::take(&mut pending)
+            .into_iter()
+            .partition(|file| sync_conflicts::is_conflict_copy(&data_dir, file));
+
+        // The sidecar only sees a vault's files once they are in its working copy
+        let changed: BTreeSet<String> = match &vault_dir {
+            Some(dir) => {
+                let files: Vec<String> = changed.into_iter().collect();
+                vault::refresh_from_vault(&app, dir, &files)
+                    .into_iter()
+                    .collect()
+            }
+            None => changed,
+        };
+
+        if !changed.is_empty() {
+            let change = build_change(&data_dir, changed);
+            info!("[Watcher] External changes: {}", change.files.join(", "));
+            let _ = app.emit("data-changed-externally", change);
+        }
+        if !copies.is_empty() {
+            let conflicts = sync_conflicts::find_conflicts(&data_dir);
+            info!("[Watcher] Sync conflict copies: {}", conflicts.len());
+            let _ = app.emit("sync-conflicts-detected", conflicts);
+        }
+    }
+}
+
+/// Watch `data_dir` for external changes, replacing the watcher of a previous directory
+/// Called whenever a sidecar is spawned; a no-op when the directory is already watched
+/// (and has not been turned into a vault or back since)
+pub(crate) fn watch(app: &tauri::AppHandle, data_dir: &str) {
+    use notify::Watcher;
+
+    let data_dir = PathBuf::from(data_dir);
+    let is_vault = vault::is_vault(&data_dir);
+    let state = app.state::<Mutex<DataWatcherState>>();
+    let mut state_guard = state.lock().unwrap();
+    if state_guard.watcher.is_some()
+        && state_guard.data_dir.as_ref() == Some(&data_dir)
+        && state_guard.vault == is_vault
+    {
+        return;
+    }
+
+    // Stop watching the previous directory first
+    state_guard.watcher = None;
+    state_guard.data_dir = None;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(w) => w,
+        Err(e) => {
+            error!("[Watcher] Failed to create file watcher: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = watcher.watch(&data_dir, notify::RecursiveMode::Recursive) {
+        error!("[Watcher] Failed to watch {:?}: {}", data_dir, e);
+        return;
+    }
+
+    // Event paths are reported in canonical form (e.g. /private/var on macOS)
+    let app_clone = app.clone();
+    let dir_clone = std::fs::canonicalize(&data_dir).unwrap_or_else(|_| data_dir.clone());
+    let vault_dir = is_vault.then(|| data_dir.clone());
+    std::thread::spawn(move || run_debouncer(app_clone, dir_clone, vault_dir, rx));
+
+    info!("[Watcher] Watching {:?} for external changes", data_dir);
+    state_guard.watcher = Some(watcher);
+    state_guard.data_dir = Some(data_dir);
+    state_guard.vault = is_vault;
+}
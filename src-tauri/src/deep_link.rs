@@ -0,0 +1,303 @@
+// `doggybag://` deep links and in-app navigation requests.
+//
+// Links open a view of the main window:
+//   doggybag://month/2026-01   detailed view of a month
+//   doggybag://calendar        calendar
+//   doggybag://claim/<id>      insurance claim
+//   doggybag://bill/<id>       bill in the setup page
+//
+// The scheme is registered by tauri-plugin-deep-link (tauri.conf.json).
+// A link that launches the app is held until the frontend asks for it with
+// `take_pending_navigation`; links arriving later (including through a second
+// launch, forwarded by the single-instance plugin) are sent to the main window
+// as a `navigate` event. The tray uses the same event. Malformed or unknown
+// links are rejected with a `deep-link-rejected` event carrying the reason.
+
+use std::sync::Mutex;
+use tauri::{Emitter, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tracing::{error, info};
+
+use crate::windows::MAIN_WINDOW;
+
+/// URL scheme handled by the app
+const SCHEME: &str = "doggybag";
+
+/// Longest accepted record id
+const MAX_ID_LEN: usize = 64;
+
+/// Where the main window should go
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(tag = "target", rename_all = "camelCase")]
+pub(crate) enum NavigateTarget {
+    Month {
+        month: String,
+    },
+    Calendar,
+    Claim {
+        id: String,
+    },
+    Bill {
+        id: String,
+    },
+    /// Month view with the Quick Add form open
+    QuickAdd {
+        month: String,
+    },
+}
+
+impl NavigateTarget {
+    /// Frontend route showing the target
+    fn route(&self) -> String {
+        match self {
+            NavigateTarget::Month { month } => format!("/month/{}", month),
+            NavigateTarget::Calendar => "/calendar".to_string(),
+            NavigateTarget::Claim { id } => format!("/insurance?claim={}", id),
+            NavigateTarget::Bill { id } => format!("/setup?tab=bills&edit={}", id),
+            NavigateTarget::QuickAdd { month } => format!("/month/{}?quickAdd=bill", month),
+        }
+    }
+}
+
+/// Payload of the `navigate` event
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NavigateEvent {
+    #[serde(flatten)]
+    target: NavigateTarget,
+    route: String,
+}
+
+/// Payload of the `deep-link-rejected` event
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeepLinkRejected {
+    url: String,
+    error: String,
+}
+
+/// Navigation requested before the frontend was listening
+#[derive(Default)]
+pub(crate) struct PendingNavigation(Option<NavigateEvent>);
+
+/// Check a `YYYY-MM` month (also used by the CLI)
+pub(crate) fn check_month(month: &str) -> Result<(), String> {
+    let valid = month.len() == 7
+        && month.as_bytes()[4] == b'-'
+        && month[..4].bytes().all(|b| b.is_ascii_digit())
+        && month[5..].bytes().all(|b| b.is_ascii_digit())
+        && month[5..]
+            .parse::<u32>()
+            .is_ok_and(|m| (1..=12).contains(&m));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not a month (expected YYYY-MM)", month))
+    }
+}
+
+fn check_id(id: &str) -> Result<(), String> {
+    if id.is_empty() || id.len() > MAX_ID_LEN {
+        return Err(format!("Id must be 1-{} characters", MAX_ID_LEN));
+    }
+    if !id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+    {
+        return Err(format!("'{}' is not a valid id", id));
+    }
+    Ok(())
+}
+
+/// Parse a `doggybag://` link into a navigation target
+fn parse(url: &Url) -> Result<NavigateTarget, String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("Unsupported scheme '{}'", url.scheme()));
+    }
+    let kind = url.host_str().unwrap_or_default();
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+
+    match (kind, segments.as_slice()) {
+        ("month", [month]) => {
+            check_month(month)?;
+            Ok(NavigateTarget::Month {
+                month: month.to_string(),
+            })
+        }
+        ("calendar", []) => Ok(NavigateTarget::Calendar),
+        ("claim", [id]) => {
+            check_id(id)?;
+            Ok(NavigateTarget::Claim { id: id.to_string() })
+        }
+        ("bill", [id]) => {
+            check_id(id)?;
+            Ok(NavigateTarget::Bill { id: id.to_string() })
+        }
+        ("month" | "claim" | "bill", _) => Err(format!(
+            "Expected {}://{}/<{}>",
+            SCHEME,
+            kind,
+            if kind == "month" { "YYYY-MM" } else { "id" }
+        )),
+        _ => Err(format!("Unknown link type '{}'", kind)),
+    }
+}
+
+/// Show the main window and send it to `target`
+pub(crate) fn navigate(app: &tauri::AppHandle, target: NavigateTarget) {
+    crate::tray::show_main_window(app);
+    let event = NavigateEvent {
+        route: target.route(),
+        target,
+    };
+    info!("[DeepLink] Navigating to {}", event.route);
+    let _ = app.emit_to(MAIN_WINDOW, "navigate", event);
+}
+
+fn handle_urls(app: &tauri::AppHandle, urls: Vec<Url>, at_launch: bool) {
+    for url in urls {
+        match parse(&url) {
+            Ok(target) if at_launch => {
+                let state = app.state::<Mutex<PendingNavigation>>();
+                state.lock().unwrap().0 = Some(NavigateEvent {
+                    route: target.route(),
+                    target,
+                });
+            }
+            Ok(target) => navigate(app, target),
+            Err(error) => {
+                error!("[DeepLink] Rejected {}: {}", url, error);
+                let rejected = DeepLinkRejected {
+                    url: url.to_string(),
+                    error,
+                };
+                let _ = app.emit_to(MAIN_WINDOW, "deep-link-rejected", rejected);
+            }
+        }
+    }
+}
+
+/// Handle the link the app was launched with and listen for later ones
+pub(crate) fn setup(app: &tauri::AppHandle) {
+    // Installed builds register the scheme on install; this covers dev builds and AppImages
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        error!("[DeepLink] Failed to register {}:// links: {}", SCHEME, e);
+    }
+
+    match app.deep_link().get_current() {
+        Ok(Some(urls)) => handle_urls(app, urls, true),
+        Ok(None) => {}
+        Err(e) => error!("[DeepLink] Failed to read launch link: {}", e),
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        handle_urls(&handle, event.urls(), false);
+    });
+}
+
+/// Navigation requested by the link the app was launched with (returned once)
+#[tauri::command]
+pub(crate) fn take_pending_navigation(app: tauri::AppHandle) -> Option<NavigateEvent> {
+    let state = app.state::<Mutex<PendingNavigation>>();
+    let pending = state.lock().unwrap().0.take();
+    pending
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(link: &str) -> Result<NavigateTarget, String> {
+        parse(&Url::parse(link).expect("valid URL"))
+    }
+
+    #[test]
+    fn parses_every_link_type() {
+        assert_eq!(
+            parse_str("doggybag://month/2026-01"),
+            Ok(NavigateTarget::Month {
+                month: "2026-01".to_string()
+            })
+        );
+        assert_eq!(
+            parse_str("doggybag://calendar"),
+            Ok(NavigateTarget::Calendar)
+        );
+        assert_eq!(
+            parse_str("doggybag://claim/abc-123_x"),
+            Ok(NavigateTarget::Claim {
+                id: "abc-123_x".to_string()
+            })
+        );
+        assert_eq!(
+            parse_str("doggybag://bill/b1/"),
+            Ok(NavigateTarget::Bill {
+                id: "b1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn routes() {
+        let route = |link: &str| parse_str(link).unwrap().route();
+        assert_eq!(route("doggybag://month/2026-01"), "/month/2026-01");
+        assert_eq!(route("doggybag://calendar"), "/calendar");
+        assert_eq!(route("doggybag://claim/c1"), "/insurance?claim=c1");
+        assert_eq!(route("doggybag://bill/b1"), "/setup?tab=bills&edit=b1");
+        let quick_add = NavigateTarget::QuickAdd {
+            month: "2026-02".to_string(),
+        };
+        assert_eq!(quick_add.route(), "/month/2026-02?quickAdd=bill");
+    }
+
+    #[test]
+    fn rejects_malformed_links() {
+        for link in [
+            "https://month/2026-01",
+            "doggybag://settings",
+            "doggybag://month",
+            "doggybag://month/2026-01/extra",
+            "doggybag://calendar/2026-01",
+            "doggybag://claim",
+            "doggybag://bill/a/b",
+            "doggybag://claim/abc%2F..",
+            "doggybag://bill/has%20space",
+            "doggybag://bill/<script>",
+        ] {
+            assert!(parse_str(link).is_err(), "{} should be rejected", link);
+        }
+        let long_id = format!("doggybag://claim/{}", "a".repeat(MAX_ID_LEN + 1));
+        assert!(parse_str(&long_id).is_err());
+        let max_id = format!("doggybag://claim/{}", "a".repeat(MAX_ID_LEN));
+        assert!(parse_str(&max_id).is_ok());
+    }
+
+    #[test]
+    fn checks_months() {
+        assert!(check_month("2026-01").is_ok());
+        assert!(check_month("1999-12").is_ok());
+        for month in [
+            "2026-00",
+            "2026-13",
+            "2026-1",
+            "26-01",
+            "2026/01",
+            "202a-01",
+            "2026-+1",
+            "",
+            "2026-01-01",
+            "2026\u{e9}01",
+        ] {
+            assert!(
+                check_month(month).is_err(),
+                "{:?} should be rejected",
+                month
+            );
+        }
+    }
+}
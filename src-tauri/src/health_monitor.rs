@@ -0,0 +1,132 @@
+// Sidecar liveness monitor: keeps probing /api/health after startup.
+//
+// The startup readiness check (wait_for_sidecar_ready) stops at the first
+// success, so a backend that is still running but deadlocked goes unnoticed.
+// This monitor probes the health endpoint on an interval with a short timeout.
+// After FAILURE_THRESHOLD consecutive failures it moves the sidecar status to
+// Degraded and, unless the `restartHungSidecar` setting is false, kills the
+// process so the supervisor respawns it. A later successful probe moves the
+// status back to Ready.
+
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+use tracing::{error, info};
+
+use crate::sidecar_status::{self, SidecarPhase};
+use crate::SidecarState;
+
+/// Time between health probes
+const PROBE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A probe that takes longer than this counts as a failure
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Consecutive failed probes before the sidecar is considered hung
+const FAILURE_THRESHOLD: u32 = 3;
+
+/// Start the background monitor (runs for the lifetime of the app)
+pub(crate) fn spawn(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let client = match crate::api_token::client(&app, PROBE_TIMEOUT) {
+            Ok(client) => client,
+            Err(e) => {
+                error!("[Health] {}", e);
+                return;
+            }
+        };
+
+        let mut consecutive_failures = 0;
+        let mut degraded = false;
+        let mut watched_generation = 0;
+
+        loop {
+            tokio::time::sleep(PROBE_INTERVAL).await;
+
+            let (endpoint, pid, generation, phase) = {
+                let state = app.state::<Mutex<SidecarState>>();
+                let state_guard = state.lock().unwrap();
+                (
+                    state_guard.endpoint.clone(),
+                    state_guard.pid,
+                    state_guard.generation,
+                    state_guard.phase.clone(),
+                )
+            };
+
+            // Start counting from scratch whenever a new sidecar process appears
+            if generation != watched_generation {
+                watched_generation = generation;
+                consecutive_failures = 0;
+                degraded = false;
+            }
+
+            // Only a sidecar that has passed its readiness check is monitored
+            if phase != SidecarPhase::Ready && phase != SidecarPhase::Degraded {
+                continue;
+            }
+            let (Some(endpoint), Some(pid)) = (endpoint, pid) else {
+                continue;
+            };
+
+            let error = match client.get(endpoint.url("/api/health")).send().await {
+                Ok(response) if response.status().is_success() => None,
+                Ok(response) => Some(format!("Health check returned {}", response.status())),
+                Err(e) if e.is_timeout() => Some(format!(
+                    "Health check timed out after {}s",
+                    PROBE_TIMEOUT.as_secs()
+                )),
+                Err(e) => Some(format!("Health check failed: {}", e)),
+            };
+
+            let Some(error) = error else {
+                if degraded {
+                    info!("[Health] Sidecar on {} is responding again", endpoint);
+                    sidecar_status::transition_if_current(
+                        &app,
+                        generation,
+                        SidecarPhase::Ready,
+                        |_| {},
+                    );
+                }
+                consecutive_failures = 0;
+                degraded = false;
+                continue;
+            };
+
+            consecutive_failures += 1;
+            info!(
+                "[Health] Probe {}/{} failed: {}",
+                consecutive_failures, FAILURE_THRESHOLD, error
+            );
+
+            if consecutive_failures < FAILURE_THRESHOLD || degraded {
+                continue;
+            }
+            degraded = true;
+
+            let restarting = crate::read_setting(&app, "restartHungSidecar")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+
+            info!(
+                "[Health] Sidecar (PID: {}) is not responding, restart: {}",
+                pid, restarting
+            );
+            sidecar_status::transition_if_current(
+                &app,
+                generation,
+                SidecarPhase::Degraded,
+                |state| state.health_error = Some(error),
+            );
+
+            // Killing without setting stop_requested makes the supervisor
+            // treat this as a crash and respawn the sidecar
+            if restarting {
+                if let Err(e) = crate::shutdown::force_kill_sidecar(&app, generation) {
+                    error!("[Health] {}", e);
+                }
+            }
+        }
+    });
+}
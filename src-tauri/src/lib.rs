@@ -5,15 +5,62 @@ use tauri::Emitter;
 use tauri::Manager;
 use tauri::RunEvent;
 use tauri::WebviewUrl;
-use tauri::WebviewWindowBuilder;
-use tauri_plugin_shell::process::CommandEvent;
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
 use tauri_plugin_shell::ShellExt;
-
-// Global state to track the sidecar process PID and port
+use tracing::{debug, error, info};
+
+mod api_proxy;
+mod api_socket;
+mod api_token;
+mod cli;
+mod data_dir_lock;
+mod data_dir_validation;
+mod data_integrity;
+mod data_migration;
+mod data_watcher;
+mod deep_link;
+mod health_monitor;
+mod logging;
+mod profiles;
+#[cfg(desktop)]
+mod quick_entry;
+mod redaction;
+mod reminders;
+mod secrets;
+mod shutdown;
+mod sidecar_status;
+mod snapshots;
+mod supervisor;
+mod sync_conflicts;
+mod tray;
+mod vault;
+mod windows;
+
+use sidecar_status::SidecarPhase;
+
+// Global state to track the sidecar process and its lifecycle
+// Only modified through sidecar_status::transition() so every change is emitted
 #[derive(Default)]
-struct SidecarState {
+pub(crate) struct SidecarState {
+    // Handle used to force kill the process (see shutdown.rs)
+    child: Option<CommandChild>,
     pid: Option<u32>,
-    port: Option<u16>,
+    // Socket or port the current sidecar listens on (see api_socket.rs)
+    endpoint: Option<api_socket::Endpoint>,
+    // DATA_DIR the current sidecar was started with (reused by the supervisor on restart)
+    data_dir: Option<String>,
+    // Incremented on every spawn so exit events from an old process can be told apart
+    generation: u64,
+    // Set when the host stops the sidecar on purpose, so the exit is not treated as a crash
+    stop_requested: bool,
+    phase: SidecarPhase,
+    phase_since: u64,
+    started_at: Option<u64>,
+    ready_at: Option<u64>,
+    next_restart_at: Option<u64>,
+    restart_count: u32,
+    exit_code: Option<i32>,
+    health_error: Option<String>,
 }
 
 // Sidecar Setup (Phase 3 Implementation):
@@ -33,14 +80,55 @@ struct SidecarState {
 // - Added read_saved_data_dir() to read from Tauri Store
 // - Added relaunch_app command for restart after migration
 // - Added .setup() hook to auto-start sidecar with saved data directory
+//
+// Sidecar supervision:
+// - Unexpected sidecar exits are restarted with backoff by supervisor.rs
+// - Readiness check lives in wait_for_sidecar_ready() and is shared with the supervisor
+// - health_monitor.rs keeps probing /api/health after startup to catch a hung backend
+// - sidecar_status.rs owns the lifecycle state machine and the sidecar-status-changed event
+// - shutdown.rs stops the sidecar via /api/shutdown, then SIGTERM, then CommandChild::kill
+// - api_token.rs generates the per-launch secret every request to the sidecar must carry
+// - api_socket.rs creates the private Unix socket the sidecar listens on (TCP port as fallback)
+// - api_proxy.rs serves dbapi://, forwarding the webview's requests to the current sidecar
+// - logging.rs writes host and sidecar output to rotating JSON-lines files (read_logs)
+// - redaction.rs masks account data and amounts in logs and re-emitted sidecar output
+//
+// Data safety:
+// - data_dir_lock.rs keeps a heartbeat lock file in DATA_DIR so only one sidecar uses it
+// - data_dir_validation.rs checks a candidate data directory before it is used
+// - data_integrity.rs parses every entities/ and months/ file before the sidecar starts
+// - data_migration.rs moves DATA_DIR to a new folder with verified copies
+// - data_watcher.rs reports changes made to DATA_DIR by other devices (cloud sync)
+// - snapshots.rs keeps versioned zip snapshots of DATA_DIR and restores them
+// - vault.rs encrypts DATA_DIR at rest and runs the sidecar on a decrypted working copy
+// - sync_conflicts.rs finds cloud-sync conflict copies and merges them by record id
+// - secrets.rs keeps secrets (e.g. the vault passphrase) in the platform keychain
+//
+// Profiles:
+// - profiles.rs keeps named data directories (e.g. personal and business budgets)
+//   and switches the sidecar between them
+//
+// Windows:
+// - windows.rs opens extra windows on a route (e.g. two months side by side);
+//   devtools commands act on the window that invoked them
+// - tray.rs shows the current month's leftover in the system tray; closing the
+//   main window hides it there and keeps the sidecar running
+// - reminders.rs raises notifications for due and overdue bills and todos; their
+//   actions (mark paid, snooze) live in the tray menu
+// - quick_entry.rs registers a global shortcut for a small ad-hoc expense window
+// - deep_link.rs opens doggybag:// links (months, claims, bills) in the main window
+//
+// Headless:
+// - cli.rs runs subcommands (export, import, verify, snapshot, leftover, serve)
+//   against a DATA_DIR without a window, for cron jobs and SSH sessions
 
 /// Helper to read debug mode setting from Tauri Store
 /// Returns false if no setting saved or on any error
-fn read_debug_mode(app: &tauri::AppHandle) -> bool {
+pub(crate) fn read_debug_mode(app: &tauri::AppHandle) -> bool {
     let config_dir = match app.path().app_config_dir() {
         Ok(dir) => dir,
         Err(e) => {
-            println!(
+            info!(
                 "[Tauri] Failed to get app_config_dir for debug mode: {:?}",
                 e
             );
@@ -50,14 +138,14 @@ fn read_debug_mode(app: &tauri::AppHandle) -> bool {
     let store_path = config_dir.join("settings.json");
 
     if !store_path.exists() {
-        println!("[Tauri] Settings file does not exist, debug mode disabled by default");
+        info!("[Tauri] Settings file does not exist, debug mode disabled by default");
         return false;
     }
 
     let content = match std::fs::read_to_string(&store_path) {
         Ok(c) => c,
         Err(e) => {
-            println!(
+            info!(
                 "[Tauri] Failed to read settings file for debug mode: {:?}",
                 e
             );
@@ -68,7 +156,7 @@ fn read_debug_mode(app: &tauri::AppHandle) -> bool {
     let json: serde_json::Value = match serde_json::from_str(&content) {
         Ok(j) => j,
         Err(e) => {
-            println!(
+            info!(
                 "[Tauri] Failed to parse settings JSON for debug mode: {:?}",
                 e
             );
@@ -81,52 +169,126 @@ fn read_debug_mode(app: &tauri::AppHandle) -> bool {
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
 
-    println!("[Tauri] Read debugMode: {}", debug_mode);
+    info!("[Tauri] Read debugMode: {}", debug_mode);
     debug_mode
 }
 
+/// Helper to read a single top-level key from the Tauri Store settings file
+/// Returns None if the file or key is missing or on any error
+pub(crate) fn read_setting(app: &tauri::AppHandle, key: &str) -> Option<serde_json::Value> {
+    let store_path = app.path().app_config_dir().ok()?.join("settings.json");
+    let content = std::fs::read_to_string(store_path).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+    json.get(key).cloned()
+}
+
+/// Helper to set a single top-level key in the Tauri Store settings file
+/// The file is replaced with a rename so a crash never leaves it half-written,
+/// then the store plugin's cached copy is reloaded so the frontend sees the change
+pub(crate) fn write_setting(
+    app: &tauri::AppHandle,
+    key: &str,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    use tauri_plugin_store::StoreExt;
+
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+    std::fs::create_dir_all(&config_dir)
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    let store_path = config_dir.join("settings.json");
+
+    let mut json = match std::fs::read_to_string(&store_path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse settings file: {}", e))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => serde_json::json!({}),
+        Err(e) => return Err(format!("Failed to read settings file: {}", e)),
+    };
+    let Some(object) = json.as_object_mut() else {
+        return Err("Settings file is not a JSON object".to_string());
+    };
+    object.insert(key.to_string(), value);
+
+    let content = serde_json::to_string_pretty(&json)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let temp_path = config_dir.join("settings.json.tmp");
+    std::fs::write(&temp_path, content)
+        .and_then(|_| std::fs::rename(&temp_path, &store_path))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&temp_path);
+            format!("Failed to write settings file: {}", e)
+        })?;
+
+    if let Some(store) = app.get_store("settings.json") {
+        if let Err(e) = store.reload() {
+            info!("[Tauri] Failed to reload settings store: {}", e);
+        }
+    }
+    info!("[Tauri] Saved setting {}", key);
+    Ok(())
+}
+
 /// Helper to read saved data directory from Tauri Store
 /// Returns None if no setting saved or on any error
-fn read_saved_data_dir(app: &tauri::AppHandle) -> Option<String> {
+pub(crate) fn read_saved_data_dir(app: &tauri::AppHandle) -> Option<String> {
     let config_dir = match app.path().app_config_dir() {
         Ok(dir) => dir,
         Err(e) => {
-            println!("[Tauri] Failed to get app_config_dir: {:?}", e);
+            info!("[Tauri] Failed to get app_config_dir: {:?}", e);
             return None;
         }
     };
     let store_path = config_dir.join("settings.json");
 
-    println!("[Tauri] Looking for settings at: {:?}", store_path);
+    info!("[Tauri] Looking for settings at: {:?}", store_path);
 
     if !store_path.exists() {
-        println!("[Tauri] Settings file does not exist");
+        info!("[Tauri] Settings file does not exist");
         return None;
     }
 
     let content = match std::fs::read_to_string(&store_path) {
         Ok(c) => c,
         Err(e) => {
-            println!("[Tauri] Failed to read settings file: {:?}", e);
+            info!("[Tauri] Failed to read settings file: {:?}", e);
             return None;
         }
     };
 
-    println!("[Tauri] Settings file content: {}", content);
+    // The whole file only in debug mode (settings can hold profile names and paths)
+    debug!("[Tauri] Settings file content: {}", content);
 
     let json: serde_json::Value = match serde_json::from_str(&content) {
         Ok(j) => j,
         Err(e) => {
-            println!("[Tauri] Failed to parse settings JSON: {:?}", e);
+            info!("[Tauri] Failed to parse settings JSON: {:?}", e);
             return None;
         }
     };
 
     let data_dir = json.get("dataDirectory")?.as_str().map(|s| s.to_string());
-    println!("[Tauri] Read dataDirectory: {:?}", data_dir);
+    info!("[Tauri] Read dataDirectory: {:?}", data_dir);
     data_dir
 }
 
+/// Resolve the data directory to use, falling back to ~/Documents/DoggyBag/
+/// when no directory (or an empty one) is given
+pub(crate) fn resolve_data_dir(data_dir: Option<String>) -> Result<String, String> {
+    match data_dir {
+        Some(dir) if !dir.is_empty() => Ok(dir),
+        _ => {
+            let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+            let default_dir = home.join("Documents").join("DoggyBag");
+            Ok(default_dir
+                .to_str()
+                .ok_or("Invalid path encoding")?
+                .to_string())
+        }
+    }
+}
+
 /// Relaunch the app (used after data directory migration or settings changes)
 /// Kills the sidecar process before restarting to prevent orphaned processes
 #[tauri::command]
@@ -139,60 +301,36 @@ fn relaunch_app(app: tauri::AppHandle) {
 }
 
 /// Open the WebView devtools (Debug Mode)
-/// This opens the browser developer tools for the WebView
+/// This opens the browser developer tools for the calling window
 #[tauri::command]
-fn open_devtools(app: tauri::AppHandle) -> Result<(), String> {
-    let window = app
-        .get_webview_window("main")
-        .ok_or("Could not find main window")?;
+fn open_devtools(window: tauri::WebviewWindow) {
     window.open_devtools();
-    Ok(())
 }
 
-/// Close the WebView devtools
+/// Close the WebView devtools of the calling window
 #[tauri::command]
-fn close_devtools(app: tauri::AppHandle) -> Result<(), String> {
-    let window = app
-        .get_webview_window("main")
-        .ok_or("Could not find main window")?;
+fn close_devtools(window: tauri::WebviewWindow) {
     window.close_devtools();
-    Ok(())
 }
 
-/// Check if devtools is currently open
+/// Check if devtools is currently open in the calling window
 #[tauri::command]
-fn is_devtools_open(app: tauri::AppHandle) -> Result<bool, String> {
-    let window = app
-        .get_webview_window("main")
-        .ok_or("Could not find main window")?;
-    Ok(window.is_devtools_open())
+fn is_devtools_open(window: tauri::WebviewWindow) -> bool {
+    window.is_devtools_open()
 }
 
-/// Toggle the WebView devtools (open if closed, close if open)
+/// Toggle the WebView devtools of the calling window (open if closed, close if open)
 #[tauri::command]
-fn toggle_devtools(app: tauri::AppHandle) -> Result<bool, String> {
-    let window = app
-        .get_webview_window("main")
-        .ok_or("Could not find main window")?;
-
+fn toggle_devtools(window: tauri::WebviewWindow) -> bool {
     if window.is_devtools_open() {
         window.close_devtools();
-        Ok(false) // devtools is now closed
+        false // devtools is now closed
     } else {
         window.open_devtools();
-        Ok(true) // devtools is now open
+        true // devtools is now open
     }
 }
 
-/// Get the current sidecar port (if running)
-/// Returns the port number or None if sidecar not ready
-#[tauri::command]
-fn get_sidecar_port(app: tauri::AppHandle) -> Option<u16> {
-    let state = app.state::<Mutex<SidecarState>>();
-    let state_guard = state.lock().unwrap();
-    state_guard.port
-}
-
 /// Get the default data directory path
 /// Returns ~/Documents/DoggyBag/ for production use
 #[tauri::command]
@@ -220,30 +358,140 @@ fn get_config_dir(app: tauri::AppHandle) -> Result<String, String> {
 }
 
 /// Internal function to start the Bun sidecar
-/// Used by both the command and the setup hook
-async fn start_bun_sidecar_internal(
+/// Used by the command, the setup hook and the supervisor
+pub(crate) async fn start_bun_sidecar_internal(
     app: &tauri::AppHandle,
     data_dir: Option<String>,
 ) -> Result<String, String> {
-    // Determine the data directory to use
-    let effective_data_dir = match data_dir {
-        Some(dir) if !dir.is_empty() => dir,
-        _ => {
-            // Use default ~/Documents/DoggyBag/
-            let home = dirs::home_dir().ok_or("Could not determine home directory")?;
-            let default_dir = home.join("Documents").join("DoggyBag");
-            default_dir
-                .to_str()
-                .ok_or("Invalid path encoding")?
-                .to_string()
+    sidecar_status::transition(app, SidecarPhase::Starting, |state| {
+        state.next_restart_at = None;
+    });
+
+    let result = match resolve_data_dir(data_dir) {
+        Ok(data_dir) => {
+            // Never run two sidecars against the same data directory; a failed start
+            // releases the lock again, except for a vault waiting for its passphrase
+            match data_dir_lock::acquire(app, &data_dir, false) {
+                // An encrypted data directory runs the sidecar on its decrypted working copy
+                Ok(()) => {
+                    vault::unlock_with_remembered_passphrase(app, &data_dir).await;
+                    match vault::sidecar_data_dir(app, &data_dir) {
+                        // Check every data file before starting a backend that would fail on it
+                        Ok(sidecar_dir) => {
+                            match verify_sidecar_data(app, &data_dir, &sidecar_dir) {
+                                Ok(()) => spawn_bun_sidecar(app, data_dir, sidecar_dir)
+                                    .await
+                                    .inspect_err(|_| data_dir_lock::release(app)),
+                                Err(report) => {
+                                    data_dir_lock::release(app);
+                                    let reason = report.summary();
+                                    let _ = app.emit("data-integrity-failed", report);
+                                    sidecar_status::transition(
+                                        app,
+                                        SidecarPhase::Failed {
+                                            reason: reason.clone(),
+                                        },
+                                        |state| {
+                                            state.pid = None;
+                                            state.endpoint = None;
+                                            state.data_dir = Some(data_dir.clone());
+                                        },
+                                    );
+                                    return Err(reason);
+                                }
+                            }
+                        }
+                        Err(vault::VaultError::Locked) => {
+                            vault::report_locked(app, &data_dir);
+                            return Err("The data directory is encrypted and locked".to_string());
+                        }
+                        Err(vault::VaultError::Io(e)) => {
+                            data_dir_lock::release(app);
+                            Err(e)
+                        }
+                    }
+                }
+                Err(data_dir_lock::LockError::Held(holder)) => {
+                    let message = format!(
+                        "Data directory is in use by {} (PID {})",
+                        holder.hostname, holder.pid
+                    );
+                    data_dir_lock::report_locked(app, &data_dir, holder);
+                    return Err(message);
+                }
+                Err(data_dir_lock::LockError::Io(e)) => Err(e),
+            }
         }
+        Err(e) => Err(e),
     };
+    if let Err(e) = &result {
+        sidecar_status::transition(app, SidecarPhase::Failed { reason: e.clone() }, |state| {
+            state.pid = None;
+            state.endpoint = None;
+        });
+    }
+    result
+}
 
+/// Parse the data files in `sidecar_dir`, the directory the sidecar runs on (the
+/// working copy of a vault); a damaged directory is reported under `data_dir`
+/// with the snapshot it can be restored from
+fn verify_sidecar_data(
+    app: &tauri::AppHandle,
+    data_dir: &str,
+    sidecar_dir: &str,
+) -> Result<(), data_integrity::DataDirectoryReport> {
+    let mut report = data_integrity::verify_data_dir(std::path::Path::new(sidecar_dir));
+    if report.is_healthy() {
+        return Ok(());
+    }
+    error!("[Tauri] Data integrity check failed: {}", report.summary());
+    report.data_dir = data_dir.to_string();
+    report.find_restore_snapshot(app);
+    if let Some(id) = &report.restore_snapshot {
+        info!("[Tauri] Damaged files can be restored from snapshot {}", id);
+    }
+    Err(report)
+}
+
+/// The backend script to run with Bun when running from a cargo build
+/// (`resource_dir` is target/debug or target/release), None for a bundled app
+/// whose sidecar has the backend compiled in
+pub(crate) fn dev_server_script(resource_dir: &std::path::Path) -> Option<PathBuf> {
+    let is_dev_mode = resource_dir.to_string_lossy().contains("target/debug")
+        || resource_dir.to_string_lossy().contains("target/release");
+    if !is_dev_mode {
+        return None;
+    }
+    // Go up from target/debug to project root, then to api/server.ts
+    Some(
+        resource_dir
+            .parent() // target
+            .and_then(|p| p.parent()) // src-tauri
+            .and_then(|p| p.parent()) // project root
+            .map(|p| p.join("api").join("server.ts"))
+            .unwrap_or_else(|| PathBuf::from("api/server.ts")),
+    )
+}
+
+/// Spawn the sidecar process and start forwarding its output
+/// `sidecar_dir` is passed as DATA_DIR; it differs from `data_dir` only for an
+/// unlocked vault (see vault.rs)
+async fn spawn_bun_sidecar(
+    app: &tauri::AppHandle,
+    effective_data_dir: String,
+    sidecar_dir: String,
+) -> Result<String, String> {
     // Ensure the data directory exists
-    let data_path = PathBuf::from(&effective_data_dir);
+    let data_path = PathBuf::from(&sidecar_dir);
     if !data_path.exists() {
-        std::fs::create_dir_all(&data_path)
-            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        std::fs::create_dir_all(&data_path).map_err(|e| {
+            format!(
+                "Failed to create data directory {}: {}",
+                data_path.display(),
+                e
+            )
+        })?;
     }
 
     // Also ensure subdirectories exist
@@ -251,14 +499,14 @@ async fn start_bun_sidecar_internal(
     let months_dir = data_path.join("months");
     if !entities_dir.exists() {
         std::fs::create_dir_all(&entities_dir)
-            .map_err(|e| format!("Failed to create entities directory: {}", e))?;
+            .map_err(|e| format!("Failed to create {}: {}", entities_dir.display(), e))?;
     }
     if !months_dir.exists() {
         std::fs::create_dir_all(&months_dir)
-            .map_err(|e| format!("Failed to create months directory: {}", e))?;
+            .map_err(|e| format!("Failed to create {}: {}", months_dir.display(), e))?;
     }
 
-    // Create sidecar command with DATA_DIR environment variable
+    // Create sidecar command with DATA_DIR and API_TOKEN environment variables
     //
     // In dev mode: The sidecar is the Bun runtime, needs "run server.ts" args
     // In production: The sidecar is a compiled binary with embedded code, no args needed
@@ -270,20 +518,10 @@ async fn start_bun_sidecar_internal(
         .resource_dir()
         .map_err(|e| format!("Failed to get resource dir: {}", e))?;
 
-    let is_dev_mode = resource_dir.to_string_lossy().contains("target/debug")
-        || resource_dir.to_string_lossy().contains("target/release");
-
-    let sidecar_command = if is_dev_mode {
+    let token = app.state::<api_token::ApiToken>().value().to_string();
+    let sidecar_command = if let Some(server_script) = dev_server_script(&resource_dir) {
         // Dev mode: Use Bun runtime with script path
-        // Go up from target/debug to project root, then to api/server.ts
-        let server_script = resource_dir
-            .parent() // target
-            .and_then(|p| p.parent()) // src-tauri
-            .and_then(|p| p.parent()) // project root
-            .map(|p| p.join("api").join("server.ts"))
-            .unwrap_or_else(|| PathBuf::from("api/server.ts"));
-
-        println!(
+        info!(
             "[Tauri] Dev mode: running Bun with script {:?}",
             server_script
         );
@@ -292,16 +530,32 @@ async fn start_bun_sidecar_internal(
             .sidecar("bun-sidecar")
             .map_err(|e| format!("Failed to create sidecar command: {}", e))?
             .args(["run", server_script.to_str().unwrap_or("api/server.ts")])
-            .env("DATA_DIR", &effective_data_dir)
+            .env("DATA_DIR", &sidecar_dir)
+            .env(api_token::TOKEN_ENV, &token)
     } else {
         // Production mode: Sidecar is a compiled binary with embedded code
         // Just run it directly, no arguments needed
-        println!("[Tauri] Production mode: running compiled sidecar binary");
+        info!("[Tauri] Production mode: running compiled sidecar binary");
 
         app.shell()
             .sidecar("bun-sidecar")
             .map_err(|e| format!("Failed to create sidecar command: {}", e))?
-            .env("DATA_DIR", &effective_data_dir)
+            .env("DATA_DIR", &sidecar_dir)
+            .env(api_token::TOKEN_ENV, &token)
+    };
+
+    // Listen on the private socket when there is one (see api_socket.rs),
+    // otherwise the backend reports an OS-assigned port as PORT=XXXX
+    let socket = app.state::<api_socket::ApiSocket>();
+    let endpoint = socket
+        .path()
+        .map(|path| api_socket::Endpoint::Socket(path.to_path_buf()));
+    let sidecar_command = match socket.path() {
+        Some(path) => {
+            socket.clear();
+            sidecar_command.env(api_socket::SOCKET_ENV, path)
+        }
+        None => sidecar_command,
     };
 
     let (mut rx, child) = sidecar_command
@@ -310,15 +564,35 @@ async fn start_bun_sidecar_internal(
 
     // Store the PID in app state
     let pid = child.pid();
-    {
-        let state = app.state::<Mutex<SidecarState>>();
-        let mut state_guard = state.lock().unwrap();
-        state_guard.pid = Some(pid);
-    }
+    let mut generation = 0;
+    // A socket's address is known up front, so there is no port to wait for
+    let phase = match endpoint {
+        Some(_) => SidecarPhase::HealthChecking,
+        None => SidecarPhase::WaitingForPort,
+    };
+    sidecar_status::transition(app, phase, |state| {
+        state.generation += 1;
+        generation = state.generation;
+        state.child = Some(child);
+        state.pid = Some(pid);
+        state.endpoint = endpoint;
+        state.data_dir = Some(effective_data_dir.clone());
+        state.stop_requested = false;
+        state.started_at = Some(sidecar_status::now_millis());
+        state.ready_at = None;
+        state.health_error = None;
+    });
+
+    // Follow the sidecar's data directory for changes made by other devices
+    data_watcher::watch(app, &effective_data_dir);
 
     let app_clone = app.clone();
-    let app_for_port = app.clone();
     let data_dir_for_log = effective_data_dir.clone();
+    // Output re-emitted to the webview is masked like the log files (see redaction.rs)
+    let redactor = app
+        .state::<std::sync::Arc<redaction::Redactor>>()
+        .inner()
+        .clone();
 
     tauri::async_runtime::spawn(async move {
         while let Some(event) = rx.recv().await {
@@ -326,46 +600,82 @@ async fn start_bun_sidecar_internal(
                 CommandEvent::Stdout(line_bytes) => {
                     let line = String::from_utf8_lossy(&line_bytes);
 
-                    // Print ALL stdout to terminal for debugging
-                    println!("[Sidecar] {}", line);
+                    // Keep ALL stdout in the terminal and the log files (see logging.rs)
+                    logging::sidecar_line(logging::SidecarStream::Stdout, &line);
 
                     // Check if this is the PORT=XXXX line from the backend
                     if let Some(port_str) = line.strip_prefix("PORT=") {
                         if port_str.trim() == "ERROR" {
-                            println!("[Tauri] Backend failed to start: PORT=ERROR received");
-                            let _ = app_clone
-                                .emit("sidecar-error", Some("Backend failed to start".to_string()));
+                            info!("[Tauri] Backend failed to start: PORT=ERROR received");
+                            sidecar_status::transition_if_current(
+                                &app_clone,
+                                generation,
+                                SidecarPhase::Failed {
+                                    reason: "Backend failed to start".to_string(),
+                                },
+                                |_| {},
+                            );
                         } else if let Ok(port) = port_str.trim().parse::<u16>() {
-                            println!("[Tauri] Captured backend port: {}", port);
-
-                            // Store port in state
-                            {
-                                let state = app_for_port.state::<Mutex<SidecarState>>();
-                                let mut state_guard = state.lock().unwrap();
-                                state_guard.port = Some(port);
-                            }
-
-                            // Don't emit sidecar-ready yet - wait for health check
-                            // The port will be used by the health check loop
+                            info!("[Tauri] Captured backend port: {}", port);
+
+                            // Store port in state (unless a newer sidecar has replaced this one)
+                            // Not ready yet - wait_for_sidecar_ready() moves on to Ready
+                            sidecar_status::transition_if_current(
+                                &app_clone,
+                                generation,
+                                SidecarPhase::HealthChecking,
+                                |state| state.endpoint = Some(api_socket::Endpoint::Port(port)),
+                            );
                         }
                     }
 
-                    let _ = app_clone.emit("bun-sidecar-output", Some(format!("{}", line)));
+                    let _ = app_clone.emit(
+                        "bun-sidecar-output",
+                        Some(redactor.redact(&line).into_owned()),
+                    );
                 }
                 CommandEvent::Stderr(line_bytes) => {
                     let line = String::from_utf8_lossy(&line_bytes);
-                    let _ = app_clone.emit("bun-sidecar-error", Some(format!("{}", line)));
-                }
-                CommandEvent::Terminated(payload) => {
+                    logging::sidecar_line(logging::SidecarStream::Stderr, &line);
                     let _ = app_clone.emit(
-                        "bun-sidecar-exited",
-                        Some(format!("Exit code: {:?}", payload.code)),
+                        "bun-sidecar-error",
+                        Some(redactor.redact(&line).into_owned()),
                     );
-                    // Clear PID and port when process exits
-                    let state = app_clone.state::<Mutex<SidecarState>>();
-                    let mut state_guard = state.lock().unwrap();
-                    state_guard.pid = None;
-                    state_guard.port = None;
+                }
+                CommandEvent::Terminated(payload) => {
+                    info!("[Tauri] Sidecar exited (code: {:?})", payload.code);
+
+                    // Ignore exits from a process that a newer sidecar has already replaced
+                    let stop_requested = {
+                        let state = app_clone.state::<Mutex<SidecarState>>();
+                        let state_guard = state.lock().unwrap();
+                        (state_guard.generation == generation).then_some(state_guard.stop_requested)
+                    };
+
+                    match stop_requested {
+                        Some(true) => {
+                            sidecar_status::transition_if_current(
+                                &app_clone,
+                                generation,
+                                SidecarPhase::Stopped,
+                                |state| {
+                                    state.child = None;
+                                    state.pid = None;
+                                    state.endpoint = None;
+                                    state.exit_code = payload.code;
+                                },
+                            );
+                        }
+                        // Hand crashes over to the supervisor for a restart
+                        Some(false) => {
+                            supervisor::handle_unexpected_exit(
+                                &app_clone,
+                                generation,
+                                payload.code,
+                            );
+                        }
+                        None => {}
+                    }
                 }
                 _ => {}
             }
@@ -378,6 +688,134 @@ async fn start_bun_sidecar_internal(
     ))
 }
 
+/// Wait for the sidecar to be reachable and pass the /api/health check
+/// Moves the status to Ready (or Failed) and returns its address once the backend is ready
+pub(crate) async fn wait_for_sidecar_ready(
+    app: &tauri::AppHandle,
+) -> Result<api_socket::Endpoint, String> {
+    let generation = {
+        let state = app.state::<Mutex<SidecarState>>();
+        let state_guard = state.lock().unwrap();
+        state_guard.generation
+    };
+
+    let result = poll_sidecar_health(app, generation).await;
+    let phase = match &result {
+        Ok(_) => SidecarPhase::Ready,
+        Err(e) => SidecarPhase::Failed { reason: e.clone() },
+    };
+    if !sidecar_status::transition_if_current(app, generation, phase, |_| {}) {
+        return Err("Sidecar was replaced while waiting for it to become ready".to_string());
+    }
+    result
+}
+
+async fn poll_sidecar_health(
+    app: &tauri::AppHandle,
+    generation: u64,
+) -> Result<api_socket::Endpoint, String> {
+    let client = api_token::client(app, std::time::Duration::from_secs(2))?;
+    let mut attempts = 0;
+    let max_attempts = 30; // 30 * 200ms = 6 seconds max wait
+
+    loop {
+        attempts += 1;
+
+        // Read the address from state (known at spawn for a socket, set by the
+        // stdout handler when it sees PORT=XXXX otherwise)
+        let (endpoint, current_generation, phase) = {
+            let state = app.state::<Mutex<SidecarState>>();
+            let state_guard = state.lock().unwrap();
+            (
+                state_guard.endpoint.clone(),
+                state_guard.generation,
+                state_guard.phase.clone(),
+            )
+        };
+
+        // Stop early if the process exited, failed or was replaced in the meantime
+        if current_generation != generation {
+            return Err("Sidecar was replaced".to_string());
+        }
+        if let SidecarPhase::Failed { reason } = phase {
+            return Err(reason);
+        }
+        if phase == SidecarPhase::Stopped || phase == SidecarPhase::Stopping {
+            return Err("Sidecar exited before it became ready".to_string());
+        }
+
+        match endpoint {
+            Some(endpoint) => {
+                // Try to reach the health endpoint (fails until the backend is listening)
+                match client.get(endpoint.url("/api/health")).send().await {
+                    Ok(response) if response.status().is_success() => {
+                        info!(
+                            "[Tauri] Backend is ready on {} after {} attempts",
+                            endpoint, attempts
+                        );
+                        return Ok(endpoint);
+                    }
+                    _ => {
+                        if attempts >= max_attempts {
+                            info!(
+                                "[Tauri] Backend health check timed out after {} attempts",
+                                attempts
+                            );
+                            return Err("Backend failed to respond to health check".to_string());
+                        }
+                    }
+                }
+            }
+            None => {
+                // Port not yet captured from stdout
+                if attempts >= max_attempts {
+                    info!(
+                        "[Tauri] Backend port capture timed out after {} attempts",
+                        attempts
+                    );
+                    return Err("Backend failed to report port".to_string());
+                }
+            }
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+}
+
+/// Take the launch snapshot, start the sidecar and wait for it to become ready
+/// (used at launch and when switching profiles)
+pub(crate) async fn launch_sidecar(app: &tauri::AppHandle, data_dir: Option<String>) {
+    // Keep a copy of the known-good data before the backend touches it; damaged
+    // data is reported by start_bun_sidecar_internal instead
+    if let Some(dir) = &data_dir {
+        if data_integrity::verify_data_dir(std::path::Path::new(dir)).is_healthy() {
+            if let Err(e) =
+                snapshots::take_snapshot(app, dir.clone(), snapshots::SnapshotReason::Launch).await
+            {
+                error!("[Tauri] Launch snapshot failed: {}", e);
+            }
+        }
+    }
+
+    info!("[Tauri] Starting sidecar with data_dir: {:?}", data_dir);
+
+    // Start the sidecar
+    match start_bun_sidecar_internal(app, data_dir.clone()).await {
+        Ok(msg) => {
+            info!("[Tauri] {}", msg);
+
+            // Wait for the backend to be ready (health check with dynamic port)
+            // The status moves to Ready or Failed and is emitted to the frontend
+            if let Err(e) = wait_for_sidecar_ready(app).await {
+                error!("[Tauri] Sidecar did not become ready: {}", e);
+            }
+        }
+        Err(e) => {
+            error!("[Tauri] Failed to start sidecar: {}", e);
+        }
+    }
+}
+
 /// Start the Bun sidecar with an optional data directory
 /// If data_dir is not provided, uses the default ~/Documents/DoggyBag/
 #[tauri::command]
@@ -385,49 +823,27 @@ async fn start_bun_sidecar(
     app: tauri::AppHandle,
     data_dir: Option<String>,
 ) -> Result<String, String> {
-    start_bun_sidecar_internal(&app, data_dir).await
+    // An explicit start clears any crash-loop state from the supervisor
+    supervisor::reset(&app);
+    let result = start_bun_sidecar_internal(&app, data_dir).await?;
+
+    // Track readiness in the background so the status reaches Ready (or Failed)
+    tauri::async_runtime::spawn(async move {
+        let _ = wait_for_sidecar_ready(&app).await;
+    });
+
+    Ok(result)
 }
 
 /// Stop the running Bun sidecar
+/// Asks the backend to flush and exit before falling back to a kill
 #[tauri::command]
 async fn stop_bun_sidecar(app: tauri::AppHandle) -> Result<String, String> {
-    let pid = {
-        let state = app.state::<Mutex<SidecarState>>();
-        let state_guard = state.lock().unwrap();
-        state_guard.pid
-    };
-
-    match pid {
-        Some(pid) => {
-            // Kill the process using system command
-            #[cfg(unix)]
-            {
-                std::process::Command::new("kill")
-                    .arg("-9")
-                    .arg(pid.to_string())
-                    .output()
-                    .map_err(|e| format!("Failed to kill sidecar: {}", e))?;
-            }
-            #[cfg(windows)]
-            {
-                std::process::Command::new("taskkill")
-                    .arg("/F")
-                    .arg("/PID")
-                    .arg(pid.to_string())
-                    .output()
-                    .map_err(|e| format!("Failed to kill sidecar: {}", e))?;
-            }
-
-            // Clear the PID and port
-            {
-                let state = app.state::<Mutex<SidecarState>>();
-                let mut state_guard = state.lock().unwrap();
-                state_guard.pid = None;
-                state_guard.port = None;
-            }
-
-            Ok(format!("Sidecar stopped (PID: {})", pid))
-        }
+    let stopped = shutdown::shutdown_sidecar(&app).await?;
+    vault::close(&app);
+    data_dir_lock::release(&app);
+    match stopped {
+        Some(pid) => Ok(format!("Sidecar stopped (PID: {})", pid)),
         None => Ok("No sidecar running".to_string()),
     }
 }
@@ -449,71 +865,118 @@ async fn restart_bun_sidecar(
     start_bun_sidecar(app, data_dir).await
 }
 
-/// Kill the sidecar process synchronously (used during app shutdown)
+/// Stop the sidecar synchronously (used during app shutdown and relaunch)
+/// Blocks until the graceful shutdown completes or the sidecar is killed
 fn kill_sidecar_sync(app: &tauri::AppHandle) {
-    let pid = {
-        let state = app.state::<Mutex<SidecarState>>();
-        let state_guard = state.lock().unwrap();
-        state_guard.pid
-    };
-
-    if let Some(pid) = pid {
-        println!("[Tauri] Shutting down sidecar (PID: {})...", pid);
-
-        #[cfg(unix)]
-        {
-            // First try graceful SIGTERM
-            let _ = std::process::Command::new("kill")
-                .arg("-15") // SIGTERM for graceful shutdown
-                .arg(pid.to_string())
-                .output();
-
-            // Give it a moment to shut down gracefully
-            std::thread::sleep(std::time::Duration::from_millis(100));
-
-            // Then force kill if still running
-            let _ = std::process::Command::new("kill")
-                .arg("-9") // SIGKILL for force kill
-                .arg(pid.to_string())
-                .output();
-        }
-
-        #[cfg(windows)]
-        {
-            let _ = std::process::Command::new("taskkill")
-                .arg("/F")
-                .arg("/PID")
-                .arg(pid.to_string())
-                .output();
-        }
-
-        println!("[Tauri] Sidecar terminated");
-
-        // Clear the state
-        let state = app.state::<Mutex<SidecarState>>();
-        let mut state_guard = state.lock().unwrap();
-        state_guard.pid = None;
-        state_guard.port = None;
+    info!("[Tauri] Shutting down sidecar...");
+    match tauri::async_runtime::block_on(shutdown::shutdown_sidecar(app)) {
+        Ok(Some(pid)) => info!("[Tauri] Sidecar terminated (PID: {})", pid),
+        Ok(None) => {}
+        Err(e) => error!("[Tauri] Failed to stop sidecar: {}", e),
     }
+    vault::close(app);
+    data_dir_lock::release(app);
+    app.state::<api_socket::ApiSocket>().remove();
+}
+
+/// Run a headless subcommand such as `doggybag export` (see cli.rs)
+/// Returns the exit code, or None when the app should start instead
+pub fn run_cli(args: Vec<String>) -> Option<i32> {
+    cli::run(args)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default();
+
+    // A second launch focuses the running window instead of starting another
+    // sidecar (must be the first plugin registered). Deep links in the second
+    // launch's arguments are forwarded to deep_link.rs
+    #[cfg(desktop)]
+    let builder = builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+        info!("[Tauri] Second instance launched, focusing main window");
+        if let Some(window) = app.get_webview_window(windows::MAIN_WINDOW) {
+            let _ = window.unminimize();
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }));
+
+    // Global shortcut for the quick entry window (see quick_entry.rs)
+    #[cfg(desktop)]
+    let builder = builder
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(quick_entry::handle_shortcut)
+                .build(),
+        )
+        .manage(Mutex::new(quick_entry::QuickEntryState::default()));
+
+    builder
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
-        .plugin(tauri_plugin_window_state::Builder::new().build())
+        .plugin(
+            tauri_plugin_window_state::Builder::new()
+                .with_denylist(&[windows::QUICK_ENTRY_WINDOW])
+                .build(),
+        )
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_deep_link::init())
         .manage(Mutex::new(SidecarState::default()))
+        .manage(api_token::ApiToken::generate())
+        .manage(Mutex::new(supervisor::SupervisorState::default()))
+        .manage(snapshots::SnapshotState::default())
+        .manage(Mutex::new(data_watcher::DataWatcherState::default()))
+        .manage(Mutex::new(data_dir_lock::DataDirLockState::default()))
+        .manage(Mutex::new(vault::VaultState::default()))
+        .manage(Mutex::new(reminders::RemindersState::default()))
+        .manage(Mutex::new(deep_link::PendingNavigation::default()))
+        .on_window_event(tray::handle_window_event)
+        .register_asynchronous_uri_scheme_protocol(api_proxy::SCHEME, api_proxy::handle)
         .invoke_handler(tauri::generate_handler![
             start_bun_sidecar,
             stop_bun_sidecar,
             restart_bun_sidecar,
             get_default_data_dir,
             get_config_dir,
-            get_sidecar_port,
+            sidecar_status::get_sidecar_status,
+            api_token::get_sidecar_credentials,
+            data_integrity::verify_data_directory,
+            data_migration::migrate_data_directory,
+            data_dir_validation::validate_data_directory,
+            data_dir_lock::take_over_data_dir,
+            vault::get_vault_status,
+            vault::unlock_vault,
+            vault::lock_vault,
+            vault::enable_vault,
+            vault::disable_vault,
+            vault::change_vault_passphrase,
+            secrets::secret_set,
+            secrets::secret_get,
+            secrets::secret_delete,
+            profiles::list_profiles,
+            profiles::create_profile,
+            profiles::update_profile,
+            profiles::delete_profile,
+            profiles::switch_profile,
+            windows::open_window,
+            deep_link::take_pending_navigation,
+            logging::read_logs,
+            #[cfg(desktop)]
+            quick_entry::get_quick_entry_shortcut,
+            #[cfg(desktop)]
+            quick_entry::set_quick_entry_shortcut,
+            #[cfg(desktop)]
+            quick_entry::close_quick_entry,
+            snapshots::list_snapshots,
+            snapshots::create_snapshot,
+            snapshots::restore_snapshot,
+            snapshots::delete_snapshot,
+            sync_conflicts::list_sync_conflicts,
+            sync_conflicts::resolve_sync_conflict,
             relaunch_app,
             open_devtools,
             close_devtools,
@@ -523,99 +986,70 @@ pub fn run() {
         .setup(|app| {
             let app_handle = app.handle().clone();
 
-            // Read debug mode setting and create window with devtools enabled/disabled accordingly
-            // This must be done in setup() because devtools cannot be toggled after window creation
-            let debug_mode = read_debug_mode(&app_handle);
-            println!("[Tauri Setup] Creating main window with devtools: {}", debug_mode);
+            // Log files first, so everything after this is kept (see logging.rs)
+            // The redactor also masks sidecar output sent to the webview
+            let redactor = std::sync::Arc::new(redaction::Redactor::from_settings(&app_handle));
+            app.manage(redactor.clone());
+            if let Err(e) = logging::init(&app_handle, redactor) {
+                eprintln!("[Logging] {}", e);
+            }
+
+            // Client used by the dbapi:// protocol (needs the managed API token and socket)
+            app.manage(api_socket::ApiSocket::create());
+            app.manage(api_proxy::ProxyClient::new(&app_handle)?);
 
             // Create the main window dynamically
-            let window_builder = WebviewWindowBuilder::new(app, "main", WebviewUrl::default())
-                .title("Doggy Bag")
-                .inner_size(800.0, 600.0)
-                .min_inner_size(600.0, 400.0);
+            // This must be done in setup() because devtools cannot be toggled after window creation
+            windows::build(
+                app,
+                windows::MAIN_WINDOW,
+                WebviewUrl::default(),
+                "Doggy Bag",
+            )
+            .expect("Failed to create main window");
+
+            // The app keeps working without a tray (closing the window then quits)
+            if let Err(e) = tray::setup(&app_handle) {
+                error!("[Tray] Failed to create tray icon: {}", e);
+            }
+
+            // Notify due and overdue bills and todos
+            reminders::spawn(app_handle.clone());
+
+            #[cfg(desktop)]
+            quick_entry::setup(&app_handle);
 
-            // Enable devtools based on debug mode setting
-            // Note: In debug builds, devtools is always available. In release builds,
-            // it requires the "devtools" feature flag AND this setting to be true.
-            #[cfg(debug_assertions)]
-            let window_builder = window_builder.devtools(true);
+            // Handle doggybag:// links (the launch link waits for the frontend)
+            deep_link::setup(&app_handle);
 
-            #[cfg(not(debug_assertions))]
-            let window_builder = window_builder.devtools(debug_mode);
+            // Keep probing the sidecar for the lifetime of the app
+            health_monitor::spawn(app_handle.clone());
 
-            window_builder
-                .build()
-                .expect("Failed to create main window");
+            // Snapshot the data directory periodically
+            snapshots::spawn_scheduler(app_handle.clone());
+
+            // Keep the data directory lock alive while the sidecar runs
+            data_dir_lock::spawn_heartbeat(app_handle.clone());
+
+            // Encrypt changes back into an unlocked vault
+            vault::spawn_flusher(app_handle.clone());
 
             // Spawn async task to start sidecar
             tauri::async_runtime::spawn(async move {
-                // Read saved directory or use default
-                let data_dir = read_saved_data_dir(&app_handle)
-                    .or_else(|| {
-                        dirs::home_dir()
-                            .map(|h| h.join("Documents").join("DoggyBag"))
-                            .and_then(|p| p.to_str().map(|s| s.to_string()))
-                    });
-
-                println!("[Tauri Setup] Starting sidecar with data_dir: {:?}", data_dir);
-
-                // Start the sidecar
-                match start_bun_sidecar_internal(&app_handle, data_dir.clone()).await {
-                    Ok(msg) => {
-                        println!("[Tauri Setup] {}", msg);
-
-                        // Wait for the backend to be ready (health check with dynamic port)
-                        let mut attempts = 0;
-                        let max_attempts = 30; // 30 * 200ms = 6 seconds max wait
-
-                        loop {
-                            attempts += 1;
-
-                            // Read port from state (set by stdout handler when it sees PORT=XXXX)
-                            let port = {
-                                let state = app_handle.state::<Mutex<SidecarState>>();
-                                let state_guard = state.lock().unwrap();
-                                state_guard.port
-                            };
-
-                            match port {
-                                Some(port) => {
-                                    // Try to reach the health endpoint on the dynamic port
-                                    let health_url = format!("http://localhost:{}/api/health", port);
-                                    match reqwest::get(&health_url).await {
-                                        Ok(response) if response.status().is_success() => {
-                                            println!("[Tauri Setup] Backend is ready on port {} after {} attempts", port, attempts);
-                                            // Emit sidecar-ready WITH the port number
-                                            let _ = app_handle.emit("sidecar-ready", port);
-                                            break;
-                                        }
-                                        _ => {
-                                            if attempts >= max_attempts {
-                                                println!("[Tauri Setup] Backend health check timed out after {} attempts", attempts);
-                                                let _ = app_handle.emit("sidecar-error", "Backend failed to respond to health check");
-                                                break;
-                                            }
-                                            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
-                                        }
-                                    }
-                                }
-                                None => {
-                                    // Port not yet captured from stdout
-                                    if attempts >= max_attempts {
-                                        println!("[Tauri Setup] Backend port capture timed out after {} attempts", attempts);
-                                        let _ = app_handle.emit("sidecar-error", "Backend failed to report port");
-                                        break;
-                                    }
-                                    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("[Tauri Setup] Failed to start sidecar: {}", e);
-                        let _ = app_handle.emit("sidecar-error", e);
-                    }
+                // With several profiles the user picks one first (see profiles.rs)
+                if profiles::needs_picker(&app_handle) {
+                    profiles::report_choosing(&app_handle);
+                    return;
                 }
+
+                // Read saved directory or use default
+                let data_dir = read_saved_data_dir(&app_handle).or_else(|| {
+                    dirs::home_dir()
+                        .map(|h| h.join("Documents").join("DoggyBag"))
+                        .and_then(|p| p.to_str().map(|s| s.to_string()))
+                });
+
+                launch_sidecar(&app_handle, data_dir).await;
             });
 
             Ok(())
@@ -626,7 +1060,7 @@ pub fn run() {
             match event {
                 RunEvent::Exit => {
                     // App is exiting - kill the sidecar process
-                    println!("[Tauri] App exiting, cleaning up sidecar...");
+                    info!("[Tauri] App exiting, cleaning up sidecar...");
                     kill_sidecar_sync(app_handle);
                 }
                 RunEvent::ExitRequested { api, .. } => {
@@ -0,0 +1,313 @@
+// Structured, rotating log files for the host and the sidecar.
+//
+// Host messages go through `tracing` (info!, warn!, error!), and every line
+// the sidecar prints on stdout or stderr is forwarded by sidecar_line() at the
+// level of its `[timestamp] [LEVEL]` prefix. Both are written to the terminal
+// and as JSON lines to `doggybag.<date>.jsonl` under the app log dir, one file
+// per day, keeping the last MAX_LOG_FILES. Every formatted line is masked by
+// the redactor first (see redaction.rs). Debug messages are only kept while
+// the debugMode setting is on (read at launch, like the rest of debug mode).
+// `read_logs` reads the files back for the in-app log viewer.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::Manager;
+use tracing::{info, Level};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::prelude::*;
+
+use crate::redaction::Redactor;
+
+const FILE_PREFIX: &str = "doggybag";
+const FILE_SUFFIX: &str = "jsonl";
+
+/// Days of logs kept on disk
+const MAX_LOG_FILES: usize = 14;
+
+/// Target of forwarded sidecar output (everything else is from the host)
+const SIDECAR_TARGET: &str = "sidecar";
+
+/// Entries returned by `read_logs` without a limit, and the most it returns
+const DEFAULT_LIMIT: usize = 500;
+const MAX_LIMIT: usize = 5000;
+
+fn log_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to get log dir: {}", e))
+}
+
+/// Writer factory that masks each log line before `inner` writes it
+struct RedactingWriter<M> {
+    inner: M,
+    redactor: Arc<Redactor>,
+}
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingWriter<M> {
+    type Writer = RedactedLine<'a, M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactedLine {
+            inner: self.inner.make_writer(),
+            redactor: &self.redactor,
+            buf: Vec::new(),
+        }
+    }
+}
+
+/// One formatted log line, held until it is complete so a value split across
+/// writes is still masked
+struct RedactedLine<'a, W: Write> {
+    inner: W,
+    redactor: &'a Redactor,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> Write for RedactedLine<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        let buf = std::mem::take(&mut self.buf);
+        if !buf.is_empty() {
+            let line = String::from_utf8_lossy(&buf);
+            self.inner
+                .write_all(self.redactor.redact(&line).as_bytes())?;
+        }
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for RedactedLine<'_, W> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Send host and sidecar messages to the terminal and the log files
+/// Called first thing in setup; messages logged before it are only lost, not fatal
+pub(crate) fn init(app: &tauri::AppHandle, redactor: Arc<Redactor>) -> Result<(), String> {
+    let level = if crate::read_debug_mode(app) {
+        LevelFilter::DEBUG
+    } else {
+        LevelFilter::INFO
+    };
+    let dir = log_dir(app)?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(FILE_PREFIX)
+        .filename_suffix(FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir)
+        .map_err(|e| format!("Failed to open log files in {}: {}", dir.display(), e))?;
+
+    let terminal = RedactingWriter {
+        inner: std::io::stdout,
+        redactor: redactor.clone(),
+    };
+    let files = RedactingWriter {
+        inner: appender,
+        redactor: redactor.clone(),
+    };
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .with_writer(terminal),
+        )
+        .with(
+            tracing_subscriber::fmt::layer()
+                .json()
+                .flatten_event(true)
+                .with_current_span(false)
+                .with_span_list(false)
+                .with_ansi(false)
+                .with_writer(files),
+        )
+        .with(level)
+        .try_init()
+        .map_err(|e| format!("Failed to set up logging: {}", e))?;
+
+    info!(
+        "[Logging] Writing {} logs to {}",
+        level.to_string().to_lowercase(),
+        dir.display()
+    );
+    redactor.log_status();
+    Ok(())
+}
+
+/// Show host messages on stderr (headless commands with --verbose)
+pub(crate) fn init_stderr() {
+    let _ = tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_target(false)
+        .try_init();
+}
+
+/// Output stream of the sidecar a line was printed on
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum SidecarStream {
+    Stdout,
+    Stderr,
+}
+
+/// Level of a backend log line (`[2025-01-01T00:00:00.000Z] [WARN] ...`)
+fn sidecar_level(line: &str) -> Option<Level> {
+    let (_, rest) = line.strip_prefix('[')?.split_once("] [")?;
+    let (level, _) = rest.split_once(']')?;
+    level.parse().ok()
+}
+
+/// Log a line of sidecar output (stderr without a level prefix counts as a warning)
+pub(crate) fn sidecar_line(stream: SidecarStream, line: &str) {
+    let line = line.trim_end();
+    let (stream, default_level) = match stream {
+        SidecarStream::Stdout => ("stdout", Level::INFO),
+        SidecarStream::Stderr => ("stderr", Level::WARN),
+    };
+    match sidecar_level(line).unwrap_or(default_level) {
+        Level::ERROR => tracing::error!(target: SIDECAR_TARGET, stream, "[Sidecar] {}", line),
+        Level::WARN => tracing::warn!(target: SIDECAR_TARGET, stream, "[Sidecar] {}", line),
+        Level::INFO => tracing::info!(target: SIDECAR_TARGET, stream, "[Sidecar] {}", line),
+        Level::DEBUG => tracing::debug!(target: SIDECAR_TARGET, stream, "[Sidecar] {}", line),
+        _ => tracing::trace!(target: SIDECAR_TARGET, stream, "[Sidecar] {}", line),
+    }
+}
+
+/// Log entry returned by `read_logs`
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LogEntry {
+    /// Unix timestamp (ms)
+    pub timestamp: u64,
+    /// "ERROR", "WARN", "INFO", "DEBUG" or "TRACE"
+    pub level: String,
+    /// "host" or "sidecar"
+    pub source: String,
+    pub message: String,
+    /// "stdout" or "stderr" for sidecar output
+    pub stream: Option<String>,
+}
+
+/// Which entries `read_logs` returns (every field is optional)
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LogFilter {
+    /// Least severe level to include ("error", "warn", "info", "debug")
+    pub level: Option<String>,
+    /// "host" or "sidecar"
+    pub source: Option<String>,
+    /// Text the message must contain (case-insensitive)
+    pub text: Option<String>,
+}
+
+impl LogFilter {
+    fn matches(&self, entry: &LogEntry, level: Option<Level>, text: Option<&str>) -> bool {
+        level.is_none_or(|level| {
+            entry
+                .level
+                .parse::<Level>()
+                .is_ok_and(|entry_level| entry_level <= level)
+        }) && self
+            .source
+            .as_ref()
+            .is_none_or(|source| *source == entry.source)
+            && text.is_none_or(|text| entry.message.to_lowercase().contains(text))
+    }
+}
+
+fn parse_entry(line: &str) -> Option<LogEntry> {
+    let json: serde_json::Value = serde_json::from_str(line).ok()?;
+    let timestamp = chrono::DateTime::parse_from_rfc3339(json.get("timestamp")?.as_str()?).ok()?;
+    let source = if json.get("target").and_then(|v| v.as_str()) == Some(SIDECAR_TARGET) {
+        "sidecar"
+    } else {
+        "host"
+    };
+    Some(LogEntry {
+        timestamp: u64::try_from(timestamp.timestamp_millis()).unwrap_or(0),
+        level: json.get("level")?.as_str()?.to_string(),
+        source: source.to_string(),
+        message: json
+            .get("message")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        stream: json
+            .get("stream")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+    })
+}
+
+/// Log files in `dir`, oldest first (the date in the name sorts them)
+fn log_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with(&format!("{}.", FILE_PREFIX))
+                        && name.ends_with(&format!(".{}", FILE_SUFFIX))
+                })
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Newest log entries matching `filter` that were logged at or after `since`
+/// (Unix ms), oldest first
+#[tauri::command]
+pub(crate) async fn read_logs(
+    app: tauri::AppHandle,
+    filter: Option<LogFilter>,
+    since: Option<u64>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let filter = filter.unwrap_or_default();
+    let level = filter
+        .level
+        .as_deref()
+        .map(|level| {
+            level
+                .parse::<Level>()
+                .map_err(|_| format!("Unknown log level: {}", level))
+        })
+        .transpose()?;
+    let text = filter.text.as_ref().map(|text| text.to_lowercase());
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+
+    // Walk back from the newest line so the limit keeps the most recent entries
+    let mut entries = Vec::new();
+    'files: for path in log_files(&log_dir(&app)?)?.iter().rev() {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        for entry in content.lines().rev().filter_map(parse_entry) {
+            if since.is_some_and(|since| entry.timestamp < since) {
+                break 'files;
+            }
+            if entries.len() >= limit {
+                break 'files;
+            }
+            if filter.matches(&entry, level, text.as_deref()) {
+                entries.push(entry);
+            }
+        }
+    }
+    entries.reverse();
+    Ok(entries)
+}
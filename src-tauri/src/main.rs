@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // Subcommands such as `doggybag export` run without a window
+    if let Some(code) = doggybag_lib::run_cli(std::env::args().skip(1).collect()) {
+        std::process::exit(code);
+    }
     doggybag_lib::run()
 }
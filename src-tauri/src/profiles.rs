@@ -0,0 +1,371 @@
+// Budget profiles (household workspaces).
+//
+// A profile is a named data directory with its own theme and debug mode, e.g.
+// a personal budget and a side-business budget. The list lives in
+// `profiles.json` in the app config dir and is only written by the host.
+//
+// The rest of the app keeps reading `dataDirectory` and `debugMode` from
+// settings.json: those keys always hold the active profile's values. Before
+// switching away, the host copies them back into the outgoing profile so
+// changes made through the settings page (or a data directory migration) stay
+// with that profile. Without a profiles.json there is a single implicit
+// "Personal" profile built from settings.json.
+//
+// Switching stops the sidecar, starts it on the other DATA_DIR and reloads
+// every window. With more than one profile, launch waits in ChoosingProfile
+// until the user picks one.
+
+use std::path::PathBuf;
+use tauri::Manager;
+use tracing::info;
+
+use crate::sidecar_status::{self, now_millis, SidecarPhase};
+
+/// Profile list file in the app config dir
+const PROFILES_FILE: &str = "profiles.json";
+
+/// Name of the profile created from the existing settings
+const DEFAULT_PROFILE_NAME: &str = "Personal";
+
+/// A named data directory with its own settings
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Profile {
+    pub id: String,
+    pub name: String,
+    pub data_directory: String,
+    /// "dark", "light" or "system"; None keeps the current theme
+    #[serde(default)]
+    pub theme_mode: Option<String>,
+    #[serde(default)]
+    pub debug_mode: bool,
+    pub created_at: u64,
+}
+
+/// Contents of `profiles.json`, also returned by `list_profiles`
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ProfileList {
+    pub active_profile_id: Option<String>,
+    pub profiles: Vec<Profile>,
+}
+
+impl ProfileList {
+    fn find(&self, id: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.id == id)
+    }
+
+    fn find_mut(&mut self, id: &str) -> Option<&mut Profile> {
+        self.profiles.iter_mut().find(|p| p.id == id)
+    }
+}
+
+fn profiles_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Could not get config dir: {}", e))?;
+    Ok(config_dir.join(PROFILES_FILE))
+}
+
+/// Current settings.json values as a profile
+fn profile_from_settings(
+    app: &tauri::AppHandle,
+    id: String,
+    name: String,
+) -> Result<Profile, String> {
+    Ok(Profile {
+        id,
+        name,
+        data_directory: crate::resolve_data_dir(crate::read_saved_data_dir(app))?,
+        theme_mode: None,
+        debug_mode: crate::read_setting(app, "debugMode")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        created_at: now_millis(),
+    })
+}
+
+/// Read the profile list, falling back to a single profile from settings.json
+fn load(app: &tauri::AppHandle) -> Result<ProfileList, String> {
+    let path = profiles_path(app)?;
+    match std::fs::read(&path) {
+        Ok(content) => serde_json::from_slice(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", PROFILES_FILE, e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let profile =
+                profile_from_settings(app, "personal".to_string(), DEFAULT_PROFILE_NAME.into())?;
+            Ok(ProfileList {
+                active_profile_id: Some(profile.id.clone()),
+                profiles: vec![profile],
+            })
+        }
+        Err(e) => Err(format!("Failed to read {}: {}", PROFILES_FILE, e)),
+    }
+}
+
+/// Write the profile list through a temp file and rename it into place
+fn save(app: &tauri::AppHandle, list: &ProfileList) -> Result<(), String> {
+    let path = profiles_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(list)
+        .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, content)
+        .and_then(|_| std::fs::rename(&temp_path, &path))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&temp_path);
+            format!("Failed to write {}: {}", PROFILES_FILE, e)
+        })
+}
+
+/// Copy settings.json values changed while the active profile was in use back into it
+fn capture_active(app: &tauri::AppHandle, list: &mut ProfileList) -> Result<(), String> {
+    let Some(active_id) = list.active_profile_id.clone() else {
+        return Ok(());
+    };
+    let current = profile_from_settings(app, active_id.clone(), String::new())?;
+    if let Some(active) = list.find_mut(&active_id) {
+        active.data_directory = current.data_directory;
+        active.debug_mode = current.debug_mode;
+    }
+    Ok(())
+}
+
+/// Lowercase id from a profile name, unique within `list`
+fn new_profile_id(list: &ProfileList, name: &str) -> String {
+    let mut slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    slug = slug
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        slug = "profile".to_string();
+    }
+
+    let mut id = slug.clone();
+    let mut suffix = 2;
+    while list.find(&id).is_some() {
+        id = format!("{}-{}", slug, suffix);
+        suffix += 1;
+    }
+    id
+}
+
+/// Whether launch should wait for the user to pick a profile
+pub(crate) fn needs_picker(app: &tauri::AppHandle) -> bool {
+    load(app).is_ok_and(|list| list.profiles.len() > 1)
+}
+
+/// Stop the sidecar and release everything tied to its data directory
+async fn stop_sidecar(app: &tauri::AppHandle) -> Result<(), String> {
+    crate::shutdown::shutdown_sidecar(app).await?;
+    crate::vault::close(app);
+    crate::data_dir_lock::release(app);
+    Ok(())
+}
+
+/// List profiles and the active one
+#[tauri::command]
+pub(crate) fn list_profiles(app: tauri::AppHandle) -> Result<ProfileList, String> {
+    let mut list = load(&app)?;
+    capture_active(&app, &mut list)?;
+    Ok(list)
+}
+
+/// Create a profile; without `data_directory` it gets a new folder next to the default one
+#[tauri::command]
+pub(crate) fn create_profile(
+    app: tauri::AppHandle,
+    name: String,
+    data_directory: Option<String>,
+) -> Result<Profile, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Profile name is required".to_string());
+    }
+    let mut list = load(&app)?;
+    if list
+        .profiles
+        .iter()
+        .any(|p| p.name.eq_ignore_ascii_case(&name))
+    {
+        return Err(format!("A profile named '{}' already exists", name));
+    }
+    capture_active(&app, &mut list)?;
+
+    let id = new_profile_id(&list, &name);
+    let data_directory = match data_directory.filter(|d| !d.trim().is_empty()) {
+        Some(dir) => dir,
+        None => {
+            let default_dir = PathBuf::from(crate::resolve_data_dir(None)?);
+            let parent = default_dir
+                .parent()
+                .ok_or("Could not determine the default data location")?;
+            parent
+                .join(format!("DoggyBag-{}", id))
+                .to_string_lossy()
+                .to_string()
+        }
+    };
+    if list
+        .profiles
+        .iter()
+        .any(|p| p.data_directory == data_directory)
+    {
+        return Err("Another profile already uses this data directory".to_string());
+    }
+    let validation = crate::data_dir_validation::validate(&app, &data_directory);
+    if let Some(error) = validation.error {
+        return Err(error);
+    }
+
+    let profile = Profile {
+        id,
+        name,
+        data_directory,
+        theme_mode: None,
+        debug_mode: false,
+        created_at: now_millis(),
+    };
+    list.profiles.push(profile.clone());
+    save(&app, &list)?;
+    info!(
+        "[Profiles] Created '{}' at {}",
+        profile.name, profile.data_directory
+    );
+    Ok(profile)
+}
+
+/// Rename a profile or change its theme
+#[tauri::command]
+pub(crate) fn update_profile(
+    app: tauri::AppHandle,
+    id: String,
+    name: Option<String>,
+    theme_mode: Option<String>,
+) -> Result<Profile, String> {
+    let mut list = load(&app)?;
+    capture_active(&app, &mut list)?;
+
+    if let Some(name) = name.as_deref().map(str::trim) {
+        if name.is_empty() {
+            return Err("Profile name is required".to_string());
+        }
+        if list
+            .profiles
+            .iter()
+            .any(|p| p.id != id && p.name.eq_ignore_ascii_case(name))
+        {
+            return Err(format!("A profile named '{}' already exists", name));
+        }
+    }
+    if let Some(mode) = theme_mode.as_deref() {
+        if !matches!(mode, "dark" | "light" | "system") {
+            return Err(format!("Unknown theme mode '{}'", mode));
+        }
+    }
+
+    let profile = list
+        .find_mut(&id)
+        .ok_or_else(|| format!("Profile '{}' not found", id))?;
+    if let Some(name) = name {
+        profile.name = name.trim().to_string();
+    }
+    if theme_mode.is_some() {
+        profile.theme_mode = theme_mode;
+    }
+    let profile = profile.clone();
+    save(&app, &list)?;
+    Ok(profile)
+}
+
+/// Remove a profile from the list (its data directory is left untouched)
+#[tauri::command]
+pub(crate) fn delete_profile(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    let mut list = load(&app)?;
+    if list.active_profile_id.as_deref() == Some(id.as_str()) {
+        return Err("The active profile cannot be deleted".to_string());
+    }
+    let before = list.profiles.len();
+    list.profiles.retain(|p| p.id != id);
+    if list.profiles.len() == before {
+        return Err(format!("Profile '{}' not found", id));
+    }
+    capture_active(&app, &mut list)?;
+    save(&app, &list)?;
+    info!("[Profiles] Deleted {}", id);
+    Ok(())
+}
+
+/// Make `id` the active profile, restart the sidecar on its data directory and
+/// reload all windows
+#[tauri::command]
+pub(crate) async fn switch_profile(app: tauri::AppHandle, id: String) -> Result<Profile, String> {
+    let mut list = load(&app)?;
+    capture_active(&app, &mut list)?;
+    let profile = list
+        .find(&id)
+        .cloned()
+        .ok_or_else(|| format!("Profile '{}' not found", id))?;
+    list.active_profile_id = Some(id);
+    save(&app, &list)?;
+
+    crate::write_setting(
+        &app,
+        "dataDirectory",
+        serde_json::json!(profile.data_directory),
+    )?;
+    crate::write_setting(&app, "debugMode", serde_json::json!(profile.debug_mode))?;
+    info!(
+        "[Profiles] Switching to '{}' ({})",
+        profile.name, profile.data_directory
+    );
+
+    // At launch the window is already showing the picker and needs no reload
+    let choosing = {
+        let state = app.state::<std::sync::Mutex<crate::SidecarState>>();
+        let state_guard = state.lock().unwrap();
+        matches!(state_guard.phase, SidecarPhase::ChoosingProfile)
+    };
+    if !choosing {
+        stop_sidecar(&app).await?;
+    }
+
+    let handle = app.clone();
+    let data_dir = profile.data_directory.clone();
+    tauri::async_runtime::spawn(async move {
+        crate::supervisor::reset(&handle);
+        crate::launch_sidecar(&handle, Some(data_dir)).await;
+    });
+
+    if !choosing {
+        for window in app.webview_windows().values() {
+            window
+                .reload()
+                .map_err(|e| format!("Failed to reload window: {}", e))?;
+        }
+    }
+    Ok(profile)
+}
+
+/// Hold the sidecar until a profile is picked (see switch_profile)
+pub(crate) fn report_choosing(app: &tauri::AppHandle) {
+    info!("[Profiles] Several profiles exist, waiting for the user to pick one");
+    sidecar_status::transition(app, SidecarPhase::ChoosingProfile, |state| {
+        state.next_restart_at = None;
+    });
+}
@@ -0,0 +1,150 @@
+// Quick entry: a global shortcut that opens a small window for ad-hoc expenses.
+//
+// The shortcut is registered with the OS by the host, so it works while the
+// app is hidden in the tray. It opens the always-on-top `quick-entry` window on
+// the /quick-add route, which posts the expense to the current month's ad-hoc
+// bills and closes itself with `close_quick_entry`.
+//
+// The shortcut is the `quickEntryShortcut` setting (e.g. "CmdOrCtrl+Shift+E");
+// without the setting DEFAULT_SHORTCUT is used and an empty string turns it
+// off. `set_quick_entry_shortcut` swaps the registration at runtime.
+
+use std::sync::Mutex;
+use tauri::{Manager, WebviewUrl};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+use tracing::{error, info};
+
+use crate::windows::QUICK_ENTRY_WINDOW;
+
+/// Shortcut used until the user picks another one
+const DEFAULT_SHORTCUT: &str = "CmdOrCtrl+Shift+E";
+
+/// Shortcut currently registered with the OS
+#[derive(Default)]
+pub(crate) struct QuickEntryState {
+    shortcut: Option<(String, Shortcut)>,
+}
+
+fn parse(shortcut: &str) -> Result<Shortcut, String> {
+    shortcut
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", shortcut, e))
+}
+
+/// Saved shortcut; None when turned off
+fn saved_shortcut(app: &tauri::AppHandle) -> Option<String> {
+    match crate::read_setting(app, "quickEntryShortcut") {
+        Some(value) => value
+            .as_str()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string),
+        None => Some(DEFAULT_SHORTCUT.to_string()),
+    }
+}
+
+/// Replace the registered shortcut with `shortcut` (None unregisters it)
+fn register(app: &tauri::AppHandle, shortcut: Option<&str>) -> Result<(), String> {
+    let new = shortcut.map(|s| parse(s).map(|parsed| (s.to_string(), parsed)));
+    let new = new.transpose()?;
+
+    let state = app.state::<Mutex<QuickEntryState>>();
+    let mut state_guard = state.lock().unwrap();
+    if let Some((_, old)) = &state_guard.shortcut {
+        let _ = app.global_shortcut().unregister(*old);
+    }
+    state_guard.shortcut = None;
+
+    if let Some((text, parsed)) = new {
+        app.global_shortcut()
+            .register(parsed)
+            .map_err(|e| format!("Could not register shortcut '{}': {}", text, e))?;
+        info!("[QuickEntry] Registered shortcut {}", text);
+        state_guard.shortcut = Some((text, parsed));
+    }
+    Ok(())
+}
+
+/// Register the saved shortcut at launch
+pub(crate) fn setup(app: &tauri::AppHandle) {
+    if let Err(e) = register(app, saved_shortcut(app).as_deref()) {
+        error!("[QuickEntry] {}", e);
+    }
+}
+
+/// Global shortcut handler (only the quick entry shortcut is registered)
+pub(crate) fn handle_shortcut(app: &tauri::AppHandle, _shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() == ShortcutState::Pressed {
+        if let Err(e) = open(app) {
+            error!("[QuickEntry] {}", e);
+        }
+    }
+}
+
+/// Show the quick entry window, creating it if needed
+pub(crate) fn open(app: &tauri::AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(QUICK_ENTRY_WINDOW) {
+        let _ = window.show();
+        return window
+            .set_focus()
+            .map_err(|e| format!("Failed to focus quick entry: {}", e));
+    }
+
+    let window = crate::windows::builder(
+        app,
+        QUICK_ENTRY_WINDOW,
+        WebviewUrl::App("quick-add".into()),
+        "Quick Add Expense",
+    )
+    .inner_size(420.0, 380.0)
+    .resizable(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .center()
+    .build()
+    .map_err(|e| format!("Failed to create quick entry window: {}", e))?;
+    let _ = window.set_focus();
+    Ok(())
+}
+
+/// Get the quick entry shortcut (null when turned off)
+#[tauri::command]
+pub(crate) fn get_quick_entry_shortcut(app: tauri::AppHandle) -> Option<String> {
+    let state = app.state::<Mutex<QuickEntryState>>();
+    let state_guard = state.lock().unwrap();
+    state_guard.shortcut.as_ref().map(|(text, _)| text.clone())
+}
+
+/// Change the quick entry shortcut (null turns it off) and save it
+#[tauri::command]
+pub(crate) fn set_quick_entry_shortcut(
+    app: tauri::AppHandle,
+    shortcut: Option<String>,
+) -> Result<(), String> {
+    let shortcut = shortcut.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    // Check the new shortcut first so a typo keeps the old one working
+    if let Some(text) = shortcut {
+        parse(text)?;
+    }
+    let previous = get_quick_entry_shortcut(app.clone());
+    if let Err(e) = register(&app, shortcut) {
+        let _ = register(&app, previous.as_deref());
+        return Err(e);
+    }
+    crate::write_setting(
+        &app,
+        "quickEntryShortcut",
+        serde_json::json!(shortcut.unwrap_or("")),
+    )
+}
+
+/// Close the quick entry window (called by the window itself after saving)
+#[tauri::command]
+pub(crate) fn close_quick_entry(app: tauri::AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(QUICK_ENTRY_WINDOW) {
+        window
+            .destroy()
+            .map_err(|e| format!("Failed to close quick entry: {}", e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,225 @@
+// Masks financial data before it is logged or sent to the webview.
+//
+// Every formatted log line (host and sidecar, terminal and files, see
+// logging.rs) and every `bun-sidecar-output` / `bun-sidecar-error` event goes
+// through Redactor::redact(). It masks the values of fields that identify
+// accounts and payees (account_number, account_url, bank_transaction_name,
+// in snake_case or camelCase), money amounts (`$1,234.56`, or a number in a
+// field such as expected_amount or bankBalances), and whatever matches the
+// regular expressions listed in the `logRedactionPatterns` setting. Quoted
+// values keep their quotes, escaped or not, so JSON log lines stay valid.
+//
+// Full content is only logged while the debugMode setting is explicitly on;
+// like the rest of debug mode, both settings are read at launch.
+
+use regex::{Captures, Regex, Replacer};
+use std::borrow::Cow;
+use tracing::{info, warn};
+
+/// Replacement for a masked value
+const MASK: &str = "[redacted]";
+
+/// Settings key holding extra patterns to mask (a list of regular expressions)
+const PATTERNS_SETTING: &str = "logRedactionPatterns";
+
+/// Fields whose value is masked whatever it is
+/// The value is a JSON string inside a JSON string (`\"...\"`, whose own quotes
+/// and backslashes appear as `\\\"` and `\\\\`), a JSON string, or a bare word.
+const SENSITIVE_FIELD: &str = r#"(?i)(?P<key>\\?"?\b(?:account_number|accountnumber|account_url|accounturl|bank_transaction_name|banktransactionname)\\?"?\s*[:=]\s*)(?P<value>\\"(?:[^"\\]|\\\\\\.|\\\\[^\\"]|\\[^"\\])*\\"|"(?:[^"\\]|\\.)*"|[^\s,;}\]"\\]+)"#;
+
+/// Number fields that hold money (cents or dollars)
+const AMOUNT_FIELD: &str = r#"(?i)(?P<key>\\?"?\b\w*(?:amount|balance|cost|leftover|reimbursement|reimbursed|income|expenses|paid|received|remaining|credit_?limit)\w*\\?"?\s*[:=]\s*)-?\d+(?:\.\d+)?\b"#;
+
+/// Formatted money such as `$1,234.56` or `-$12`
+const CURRENCY: &str = r"-?\$\s?\d[\d,]*(?:\.\d+)?";
+
+pub(crate) struct Redactor {
+    enabled: bool,
+    sensitive_field: Regex,
+    amount_field: Regex,
+    currency: Regex,
+    /// Extra patterns from the settings
+    patterns: Vec<Regex>,
+    /// Settings patterns that failed to compile, reported by log_status()
+    rejected: Vec<String>,
+}
+
+impl Redactor {
+    /// `patterns` that are not valid regular expressions are skipped
+    pub(crate) fn new(enabled: bool, patterns: &[String]) -> Redactor {
+        let mut rejected = Vec::new();
+        let patterns = patterns
+            .iter()
+            .filter_map(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    rejected.push(format!("{:?}: {}", pattern, e));
+                    None
+                }
+            })
+            .collect();
+        Redactor {
+            enabled,
+            sensitive_field: Regex::new(SENSITIVE_FIELD).expect("valid sensitive field pattern"),
+            amount_field: Regex::new(AMOUNT_FIELD).expect("valid amount field pattern"),
+            currency: Regex::new(CURRENCY).expect("valid currency pattern"),
+            patterns,
+            rejected,
+        }
+    }
+
+    /// Redactor for this launch: off in debug mode, with the patterns from the settings
+    pub(crate) fn from_settings(app: &tauri::AppHandle) -> Redactor {
+        let patterns: Vec<String> = crate::read_setting(app, PATTERNS_SETTING)
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default();
+        Redactor::new(!crate::read_debug_mode(app), &patterns)
+    }
+
+    /// Log whether content is masked (called once the logger is set up)
+    pub(crate) fn log_status(&self) {
+        if self.enabled {
+            info!(
+                "[Redaction] Masking financial data in logs ({} extra patterns)",
+                self.patterns.len()
+            );
+        } else {
+            warn!("[Redaction] Debug mode is on, logging full content");
+        }
+        for rejected in &self.rejected {
+            warn!("[Redaction] Ignoring pattern {}", rejected);
+        }
+    }
+
+    /// `text` with every sensitive value masked (unchanged while redaction is off)
+    pub(crate) fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        if !self.enabled {
+            return text;
+        }
+        replace(&mut text, &self.sensitive_field, |caps: &Captures| {
+            let value = &caps["value"];
+            let quote = if value.starts_with("\\\"") {
+                "\\\""
+            } else if value.starts_with('"') {
+                "\""
+            } else {
+                ""
+            };
+            format!("{}{}{}{}", &caps["key"], quote, MASK, quote)
+        });
+        replace(
+            &mut text,
+            &self.amount_field,
+            format!("${{key}}{}", MASK).as_str(),
+        );
+        replace(&mut text, &self.currency, MASK);
+        for pattern in &self.patterns {
+            replace(&mut text, pattern, MASK);
+        }
+        text
+    }
+}
+
+/// Replace every match of `regex` in `text`, without copying when nothing matches
+fn replace(text: &mut Cow<'_, str>, regex: &Regex, replacement: impl Replacer) {
+    let masked = match regex.replace_all(text, replacement) {
+        Cow::Owned(masked) => masked,
+        Cow::Borrowed(_) => return,
+    };
+    *text = Cow::Owned(masked);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redact(text: &str) -> String {
+        Redactor::new(true, &[]).redact(text).into_owned()
+    }
+
+    #[test]
+    fn masks_sensitive_fields_in_plain_json() {
+        assert_eq!(
+            redact(r#"{"id":"b1","account_number":"123456789","name":"Rent"}"#),
+            r#"{"id":"b1","account_number":"[redacted]","name":"Rent"}"#
+        );
+        assert_eq!(
+            redact(r#"{"bank_transaction_name": "ACME \"PAYROLL\" 42"}"#),
+            r#"{"bank_transaction_name": "[redacted]"}"#
+        );
+    }
+
+    #[test]
+    fn masks_sensitive_fields_in_escaped_json() {
+        // A JSON body logged inside the `message` string of a JSON log line
+        let line = r#"{"level":"INFO","message":"Saved {\"account_number\":\"123\",\"account_url\":\"https://bank.example/acct/9\"}"}"#;
+        let masked = redact(line);
+        assert_eq!(
+            masked,
+            r#"{"level":"INFO","message":"Saved {\"account_number\":\"[redacted]\",\"account_url\":\"[redacted]\"}"}"#
+        );
+        assert!(serde_json::from_str::<serde_json::Value>(&masked).is_ok());
+
+        // Quotes and backslashes inside the payee name do not end the value early
+        let line =
+            r#"{"message":"{\"bank_transaction_name\":\"ACME \\\"PAY\\\" \\\\\",\"id\":\"t1\"}"}"#;
+        let masked = redact(line);
+        assert_eq!(
+            masked,
+            r#"{"message":"{\"bank_transaction_name\":\"[redacted]\",\"id\":\"t1\"}"}"#
+        );
+        assert!(serde_json::from_str::<serde_json::Value>(&masked).is_ok());
+    }
+
+    #[test]
+    fn masks_camel_case_and_unquoted_fields() {
+        assert_eq!(
+            redact(r#"{"accountNumber":"987","bankTransactionName":"COFFEE"}"#),
+            r#"{"accountNumber":"[redacted]","bankTransactionName":"[redacted]"}"#
+        );
+        assert_eq!(
+            redact("Updating source account_number=12345678, accountUrl: https://x.example/a"),
+            "Updating source account_number=[redacted], accountUrl: [redacted]"
+        );
+    }
+
+    #[test]
+    fn masks_amounts() {
+        assert_eq!(
+            redact(
+                r#"{"expected_amount":125000,"actualAmount":-42.5,"bankBalances":{"checking":10}}"#
+            ),
+            r#"{"expected_amount":[redacted],"actualAmount":[redacted],"bankBalances":{"checking":10}}"#
+        );
+        assert_eq!(
+            redact("Paid $1,234.56 of -$12"),
+            "Paid [redacted] of [redacted]"
+        );
+        assert_eq!(redact(r#"{\"amount\":5000}"#), r#"{\"amount\":[redacted]}"#);
+    }
+
+    #[test]
+    fn leaves_other_fields_alone() {
+        let text = r#"{"id":"b1","name":"Rent","month":"2026-01","day_of_month":15,"count":3,"version":"1.2.0","PORT":3000}"#;
+        assert_eq!(redact(text), text);
+        assert!(matches!(
+            Redactor::new(true, &[]).redact(text),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn disabled_redactor_changes_nothing() {
+        let text = r#"{"account_number":"123","amount":5,"total":"$10.00"}"#;
+        assert_eq!(Redactor::new(false, &[]).redact(text), text);
+    }
+
+    #[test]
+    fn applies_extra_patterns_and_skips_invalid_ones() {
+        let redactor = Redactor::new(true, &["ACME-\\d+".to_string(), "(".to_string()]);
+        assert_eq!(redactor.patterns.len(), 1);
+        assert_eq!(redactor.rejected.len(), 1);
+        assert_eq!(redactor.redact("Paid ACME-991"), "Paid [redacted]");
+    }
+}
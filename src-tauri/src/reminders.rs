@@ -0,0 +1,548 @@
+// Desktop notifications for due and overdue bills and todos.
+//
+// Every REFRESH_INTERVAL (and whenever the sidecar becomes ready) the host
+// reads the open bill occurrences and pending todo instances of the previous,
+// current and next month from the sidecar and raises an OS notification
+// when an item enters a stage: `reminderLeadDays` days before it is due
+// (default 3), on the due day, and once it is overdue. Each stage is notified
+// once; what was sent is kept in `reminders.json` in the app config dir so a
+// restart does not repeat it.
+//
+// Desktop notifications cannot carry buttons on every platform, so the
+// actions live in the tray's Reminders menu: "Mark Paid" (or "Mark Done")
+// closes the occurrence or completes the todo through the API, and "Snooze"
+// hides the item for a day and notifies it again afterwards.
+//
+// Settings (settings.json): `remindersEnabled` (default true),
+// `reminderLeadDays`, and `quietHoursStart` / `quietHoursEnd` ("22:00" /
+// "07:00") during which notifications wait until the quiet hours end.
+
+use chrono::{Datelike, Local, NaiveDate, NaiveTime};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Listener, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::Notify;
+use tracing::{error, info};
+
+use crate::api_socket::Endpoint;
+use crate::sidecar_status::{now_millis, SidecarPhase};
+use crate::SidecarState;
+
+/// Time between reminder checks
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// A request that takes longer than this is abandoned
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reminder bookkeeping file in the app config dir
+const REMINDERS_FILE: &str = "reminders.json";
+
+/// Default days before the due date for the first reminder
+const DEFAULT_LEAD_DAYS: i64 = 3;
+
+/// How long "Snooze" hides a reminder
+const SNOOZE_MILLIS: u64 = 24 * 60 * 60 * 1000;
+
+/// Prefix of the tray menu ids of reminder actions
+pub(crate) const MENU_PREFIX: &str = "reminder:";
+
+/// Emitted after a reminder action changed data (payload: the month)
+const COMPLETED_EVENT: &str = "reminder-completed";
+
+/// Notification stage of an item, in the order they are reached
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+enum Stage {
+    Upcoming,
+    DueToday,
+    Overdue,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum ReminderKind {
+    /// Bill occurrence: instance id and occurrence id
+    Bill {
+        instance_id: String,
+        occurrence_id: String,
+    },
+    /// Todo instance id
+    Todo { instance_id: String },
+}
+
+/// An open bill occurrence or pending todo with a due date
+#[derive(Clone, Debug)]
+pub(crate) struct Reminder {
+    /// Stable id, e.g. `bill:2026-01:<instance>:<occurrence>`
+    key: String,
+    kind: ReminderKind,
+    month: String,
+    name: String,
+    /// Cents; None for todos
+    amount: Option<i64>,
+    due_date: NaiveDate,
+}
+
+/// Contents of `reminders.json`
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReminderLog {
+    /// Last stage notified per reminder key
+    notified: BTreeMap<String, Stage>,
+    /// Reminder key -> unix millis until which it is snoozed
+    snoozed: BTreeMap<String, u64>,
+}
+
+/// Reminders currently offered in the tray menu
+#[derive(Default)]
+pub(crate) struct RemindersState {
+    active: Vec<Reminder>,
+}
+
+fn log_path(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(REMINDERS_FILE))
+}
+
+fn load_log(app: &tauri::AppHandle) -> ReminderLog {
+    log_path(app)
+        .and_then(|path| std::fs::read(path).ok())
+        .and_then(|content| serde_json::from_slice(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_log(app: &tauri::AppHandle, log: &ReminderLog) {
+    let Some(path) = log_path(app) else {
+        return;
+    };
+    let Ok(content) = serde_json::to_string_pretty(log) else {
+        return;
+    };
+    let temp_path = path.with_extension("json.tmp");
+    if let Err(e) =
+        std::fs::write(&temp_path, content).and_then(|_| std::fs::rename(&temp_path, &path))
+    {
+        let _ = std::fs::remove_file(&temp_path);
+        error!("[Reminders] Failed to write {}: {}", REMINDERS_FILE, e);
+    }
+}
+
+fn enabled(app: &tauri::AppHandle) -> bool {
+    crate::read_setting(app, "remindersEnabled")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true)
+}
+
+fn lead_days(app: &tauri::AppHandle) -> i64 {
+    crate::read_setting(app, "reminderLeadDays")
+        .and_then(|v| v.as_i64())
+        .filter(|days| (0..=30).contains(days))
+        .unwrap_or(DEFAULT_LEAD_DAYS)
+}
+
+fn read_time_setting(app: &tauri::AppHandle, key: &str) -> Option<NaiveTime> {
+    let value = crate::read_setting(app, key)?;
+    NaiveTime::parse_from_str(value.as_str()?, "%H:%M").ok()
+}
+
+/// Whether `now` falls in the configured quiet hours (which may span midnight)
+fn in_quiet_hours(app: &tauri::AppHandle, now: NaiveTime) -> bool {
+    let (Some(start), Some(end)) = (
+        read_time_setting(app, "quietHoursStart"),
+        read_time_setting(app, "quietHoursEnd"),
+    ) else {
+        return false;
+    };
+    if start <= end {
+        now >= start && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+fn stage(due_date: NaiveDate, today: NaiveDate, lead_days: i64) -> Option<Stage> {
+    let days_left = (due_date - today).num_days();
+    match days_left {
+        d if d < 0 => Some(Stage::Overdue),
+        0 => Some(Stage::DueToday),
+        d if d <= lead_days => Some(Stage::Upcoming),
+        _ => None,
+    }
+}
+
+/// Previous, current and next month, e.g. ["2025-12", "2026-01", "2026-02"]
+fn months_around(today: NaiveDate) -> Vec<String> {
+    let first = today.with_day0(0).unwrap_or(today);
+    let previous = first.pred_opt().unwrap_or(first);
+    let next = first
+        .checked_add_months(chrono::Months::new(1))
+        .unwrap_or(first);
+    [previous, first, next]
+        .iter()
+        .map(|d| d.format("%Y-%m").to_string())
+        .collect()
+}
+
+async fn get_json(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<Option<serde_json::Value>, String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()));
+    }
+    response
+        .json()
+        .await
+        .map(Some)
+        .map_err(|e| format!("Invalid response from {}: {}", url, e))
+}
+
+fn parse_date(value: &serde_json::Value) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.as_str()?, "%Y-%m-%d").ok()
+}
+
+/// Open bill occurrences and pending todos of `month`
+async fn fetch_month(
+    client: &reqwest::Client,
+    endpoint: &Endpoint,
+    month: &str,
+) -> Result<Vec<Reminder>, String> {
+    let base = endpoint.url(&format!("/api/months/{}", month));
+    let mut reminders = Vec::new();
+
+    // A month that was never generated has nothing to remind about
+    if let Some(detailed) = get_json(client, &format!("{}/detailed", base)).await? {
+        let sections = detailed
+            .get("billSections")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+        let bills = sections
+            .iter()
+            .filter_map(|section| section.get("items")?.as_array())
+            .flatten()
+            .filter(|bill| {
+                !bill
+                    .get("is_virtual")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false)
+            });
+        for bill in bills {
+            let (Some(instance_id), Some(name)) = (
+                bill.get("id").and_then(|v| v.as_str()),
+                bill.get("name").and_then(|v| v.as_str()),
+            ) else {
+                continue;
+            };
+            let occurrences = bill
+                .get("occurrences")
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default();
+            for occurrence in occurrences {
+                if occurrence.get("is_closed").and_then(|v| v.as_bool()) != Some(false) {
+                    continue;
+                }
+                let (Some(occurrence_id), Some(due_date)) = (
+                    occurrence.get("id").and_then(|v| v.as_str()),
+                    occurrence.get("expected_date").and_then(parse_date),
+                ) else {
+                    continue;
+                };
+                reminders.push(Reminder {
+                    key: format!("bill:{}:{}:{}", month, instance_id, occurrence_id),
+                    kind: ReminderKind::Bill {
+                        instance_id: instance_id.to_string(),
+                        occurrence_id: occurrence_id.to_string(),
+                    },
+                    month: month.to_string(),
+                    name: name.to_string(),
+                    amount: occurrence
+                        .get("expected_amount")
+                        .and_then(|v| v.as_f64())
+                        .map(|v| v.round() as i64),
+                    due_date,
+                });
+            }
+        }
+    }
+
+    if let Some(todos) = get_json(client, &format!("{}/todos?status=pending", base)).await? {
+        for todo in todos.as_array().cloned().unwrap_or_default() {
+            let (Some(instance_id), Some(title), Some(due_date)) = (
+                todo.get("id").and_then(|v| v.as_str()),
+                todo.get("title").and_then(|v| v.as_str()),
+                todo.get("due_date").and_then(parse_date),
+            ) else {
+                continue;
+            };
+            reminders.push(Reminder {
+                key: format!("todo:{}:{}", month, instance_id),
+                kind: ReminderKind::Todo {
+                    instance_id: instance_id.to_string(),
+                },
+                month: month.to_string(),
+                name: title.to_string(),
+                amount: None,
+                due_date,
+            });
+        }
+    }
+
+    Ok(reminders)
+}
+
+fn notification_text(reminder: &Reminder, stage: Stage, today: NaiveDate) -> (String, String) {
+    let what = match reminder.amount {
+        Some(cents) => format!("{} ({})", reminder.name, crate::tray::format_cents(cents)),
+        None => reminder.name.clone(),
+    };
+    let days = (reminder.due_date - today).num_days();
+    match stage {
+        Stage::Upcoming => (
+            "Coming up".to_string(),
+            format!(
+                "{} is due in {} day{}",
+                what,
+                days,
+                if days == 1 { "" } else { "s" }
+            ),
+        ),
+        Stage::DueToday => ("Due today".to_string(), format!("{} is due today", what)),
+        Stage::Overdue => (
+            "Overdue".to_string(),
+            format!("{} was due on {}", what, reminder.due_date.format("%b %-d")),
+        ),
+    }
+}
+
+/// Tray menu label of a reminder
+fn menu_label(reminder: &Reminder, today: NaiveDate) -> String {
+    let days = (reminder.due_date - today).num_days();
+    let when = match days {
+        d if d < 0 => "overdue".to_string(),
+        0 => "due today".to_string(),
+        1 => "due tomorrow".to_string(),
+        d => format!("due in {} days", d),
+    };
+    format!("{} - {}", reminder.name, when)
+}
+
+/// Tray menu entries for `active`: (key, label, whether it is a bill)
+fn tray_items(active: &[Reminder]) -> Vec<(String, String, bool)> {
+    let today = Local::now().date_naive();
+    active
+        .iter()
+        .map(|r| {
+            (
+                r.key.clone(),
+                menu_label(r, today),
+                matches!(r.kind, ReminderKind::Bill { .. }),
+            )
+        })
+        .collect()
+}
+
+/// Notify newly reached stages and offer the due items in the tray
+fn process(app: &tauri::AppHandle, reminders: Vec<Reminder>) {
+    let now = Local::now();
+    let today = now.date_naive();
+    let lead_days = lead_days(app);
+    let now_ms = now_millis();
+    let quiet = in_quiet_hours(app, now.time());
+
+    let mut log = load_log(app);
+    log.snoozed.retain(|_, until| *until > now_ms);
+    // Items that were paid or deleted no longer need bookkeeping
+    log.notified
+        .retain(|key, _| reminders.iter().any(|r| &r.key == key));
+
+    let mut active = Vec::new();
+    for reminder in reminders {
+        let Some(stage) = stage(reminder.due_date, today, lead_days) else {
+            continue;
+        };
+        if log.snoozed.contains_key(&reminder.key) {
+            continue;
+        }
+        let already = log.notified.get(&reminder.key).is_some_and(|s| *s >= stage);
+        if !already && !quiet {
+            let (title, body) = notification_text(&reminder, stage, today);
+            match app.notification().builder().title(title).body(body).show() {
+                Ok(()) => {
+                    log.notified.insert(reminder.key.clone(), stage);
+                }
+                Err(e) => error!("[Reminders] Failed to show notification: {}", e),
+            }
+        }
+        active.push(reminder);
+    }
+    active.sort_by(|a, b| a.due_date.cmp(&b.due_date).then(a.name.cmp(&b.name)));
+    save_log(app, &log);
+
+    crate::tray::set_reminders(app, &tray_items(&active));
+
+    let state = app.state::<Mutex<RemindersState>>();
+    state.lock().unwrap().active = active;
+}
+
+/// Start checking for due items (runs for the lifetime of the app)
+pub(crate) fn spawn(app: tauri::AppHandle) {
+    let refresh = Arc::new(Notify::new());
+    {
+        let refresh = refresh.clone();
+        app.listen("sidecar-status-changed", move |_| refresh.notify_one());
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let client = match crate::api_token::client(&app, REQUEST_TIMEOUT) {
+            Ok(client) => client,
+            Err(e) => {
+                error!("[Reminders] {}", e);
+                return;
+            }
+        };
+
+        loop {
+            let endpoint = {
+                let state = app.state::<Mutex<SidecarState>>();
+                let state_guard = state.lock().unwrap();
+                match state_guard.phase {
+                    SidecarPhase::Ready | SidecarPhase::Degraded => state_guard.endpoint.clone(),
+                    _ => None,
+                }
+            };
+
+            if let Some(endpoint) = endpoint.filter(|_| enabled(&app)) {
+                let mut reminders = Vec::new();
+                let mut failed = false;
+                for month in months_around(Local::now().date_naive()) {
+                    match fetch_month(&client, &endpoint, &month).await {
+                        Ok(found) => reminders.extend(found),
+                        Err(e) => {
+                            error!("[Reminders] Failed to read {}: {}", month, e);
+                            failed = true;
+                            break;
+                        }
+                    }
+                }
+                // A partial list would drop the bookkeeping of the missing months
+                if !failed {
+                    process(&app, reminders);
+                }
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(REFRESH_INTERVAL) => {}
+                _ = refresh.notified() => {}
+            }
+        }
+    });
+}
+
+/// Close the occurrence or complete the todo behind `reminder`
+async fn complete(
+    app: &tauri::AppHandle,
+    endpoint: &Endpoint,
+    reminder: &Reminder,
+) -> Result<(), String> {
+    let base = endpoint.url(&format!("/api/months/{}", reminder.month));
+    let url = match &reminder.kind {
+        ReminderKind::Bill {
+            instance_id,
+            occurrence_id,
+        } => format!(
+            "{}/bills/{}/occurrences/{}/close",
+            base, instance_id, occurrence_id
+        ),
+        ReminderKind::Todo { instance_id } => format!("{}/todos/{}/complete", base, instance_id),
+    };
+    let response = crate::api_token::client(app, REQUEST_TIMEOUT)?
+        .post(&url)
+        .json(&serde_json::json!({}))
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Backend returned {}", response.status()));
+    }
+    Ok(())
+}
+
+/// Handle "Mark Paid" / "Snooze" from the tray menu (`reminder:<action>:<key>`)
+pub(crate) fn handle_menu_action(app: &tauri::AppHandle, menu_id: &str) {
+    let Some((action, key)) = menu_id
+        .strip_prefix(MENU_PREFIX)
+        .and_then(|rest| rest.split_once(':'))
+    else {
+        return;
+    };
+    let reminder = {
+        let state = app.state::<Mutex<RemindersState>>();
+        let state_guard = state.lock().unwrap();
+        state_guard.active.iter().find(|r| r.key == key).cloned()
+    };
+    let Some(reminder) = reminder else {
+        return;
+    };
+
+    match action {
+        "snooze" => {
+            info!("[Reminders] Snoozed {}", reminder.name);
+            let mut log = load_log(app);
+            log.snoozed
+                .insert(reminder.key.clone(), now_millis() + SNOOZE_MILLIS);
+            // Notify the current stage again once the snooze ends
+            log.notified.remove(&reminder.key);
+            save_log(app, &log);
+            remove_active(app, &reminder.key);
+        }
+        "complete" => {
+            let endpoint = {
+                let state = app.state::<Mutex<SidecarState>>();
+                let state_guard = state.lock().unwrap();
+                state_guard.endpoint.clone()
+            };
+            let Some(endpoint) = endpoint else {
+                return;
+            };
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                match complete(&app, &endpoint, &reminder).await {
+                    Ok(()) => {
+                        info!("[Reminders] Marked {} as done", reminder.name);
+                        remove_active(&app, &reminder.key);
+                        let _ = app.emit(COMPLETED_EVENT, &reminder.month);
+                    }
+                    Err(e) => error!("[Reminders] Failed to complete {}: {}", reminder.name, e),
+                }
+            });
+        }
+        _ => {}
+    }
+}
+
+fn remove_active(app: &tauri::AppHandle, key: &str) {
+    let items = {
+        let state = app.state::<Mutex<RemindersState>>();
+        let mut state_guard = state.lock().unwrap();
+        state_guard.active.retain(|r| r.key != key);
+        tray_items(&state_guard.active)
+    };
+    crate::tray::set_reminders(app, &items);
+}
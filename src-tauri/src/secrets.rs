@@ -0,0 +1,178 @@
+// Secret storage backed by the platform keychain.
+//
+// Secrets (the remembered vault passphrase, bank portal credentials) never go
+// into settings.json. They are stored in the OS secret store - Keychain on
+// macOS, Credential Manager on Windows, Secret Service (libsecret) on Linux -
+// under the app identifier (com.bradhannah.doggybag) as the service name.
+//
+// Headless test runs have no secret store: setting DOGGYBAG_SECRETS_FILE
+// switches to a JSON file at that path instead (readable only by the user,
+// but not encrypted, so never use it for real data).
+//
+// Keys starting with `vault:` are reserved for the host (see vault.rs) and
+// cannot be read or written through the commands, so the remembered vault
+// passphrase never reaches the frontend.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+/// Environment variable selecting the file-based store
+const SECRETS_FILE_ENV: &str = "DOGGYBAG_SECRETS_FILE";
+
+/// Prefix of keys only the host may use
+const RESERVED_PREFIX: &str = "vault:";
+
+/// Longest accepted key
+const MAX_KEY_LEN: usize = 128;
+
+enum Backend {
+    Keychain(String),
+    File(PathBuf),
+}
+
+fn backend(app: &tauri::AppHandle) -> Backend {
+    match std::env::var_os(SECRETS_FILE_ENV) {
+        Some(path) if !path.is_empty() => Backend::File(PathBuf::from(path)),
+        _ => Backend::Keychain(app.config().identifier.clone()),
+    }
+}
+
+fn keychain_entry(service: &str, key: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(service, key).map_err(|e| format!("Secret store unavailable: {}", e))
+}
+
+fn read_file(path: &Path) -> Result<BTreeMap<String, String>, String> {
+    match std::fs::read(path) {
+        Ok(content) => serde_json::from_slice(&content)
+            .map_err(|e| format!("Failed to parse secrets file: {}", e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(format!("Failed to read secrets file: {}", e)),
+    }
+}
+
+/// Write the secrets file through a temp file created with owner-only permissions
+fn write_file(path: &Path, secrets: &BTreeMap<String, String>) -> Result<(), String> {
+    use std::io::Write;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create secrets directory: {}", e))?;
+    }
+    let content = Zeroizing::new(
+        serde_json::to_vec_pretty(secrets)
+            .map_err(|e| format!("Failed to serialize secrets: {}", e))?,
+    );
+    let temp_path = PathBuf::from(format!("{}.tmp", path.to_string_lossy()));
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let result = options
+        .open(&temp_path)
+        .and_then(|mut file| file.write_all(&content))
+        .and_then(|_| std::fs::rename(&temp_path, path));
+    result.map_err(|e| {
+        let _ = std::fs::remove_file(&temp_path);
+        format!("Failed to write secrets file: {}", e)
+    })
+}
+
+/// Read a secret; Ok(None) if it is not set
+pub(crate) fn get(app: &tauri::AppHandle, key: &str) -> Result<Option<String>, String> {
+    match backend(app) {
+        Backend::Keychain(service) => match keychain_entry(&service, key)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Failed to read secret: {}", e)),
+        },
+        Backend::File(path) => Ok(read_file(&path)?.remove(key)),
+    }
+}
+
+/// Store a secret, replacing any previous value
+pub(crate) fn set(app: &tauri::AppHandle, key: &str, value: &str) -> Result<(), String> {
+    match backend(app) {
+        Backend::Keychain(service) => keychain_entry(&service, key)?
+            .set_password(value)
+            .map_err(|e| format!("Failed to store secret: {}", e)),
+        Backend::File(path) => {
+            let mut secrets = read_file(&path)?;
+            secrets.insert(key.to_string(), value.to_string());
+            write_file(&path, &secrets)
+        }
+    }
+}
+
+/// Remove a secret; removing a missing secret is not an error
+pub(crate) fn delete(app: &tauri::AppHandle, key: &str) -> Result<(), String> {
+    match backend(app) {
+        Backend::Keychain(service) => match keychain_entry(&service, key)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Failed to delete secret: {}", e)),
+        },
+        Backend::File(path) => {
+            let mut secrets = read_file(&path)?;
+            if secrets.remove(key).is_some() {
+                write_file(&path, &secrets)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Validate a key passed in from the frontend
+fn check_key(key: &str) -> Result<(), String> {
+    if key.is_empty() || key.len() > MAX_KEY_LEN {
+        return Err(format!("Secret key must be 1-{} characters", MAX_KEY_LEN));
+    }
+    if !key
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | ':'))
+    {
+        return Err("Secret key may only contain letters, digits, '.', '_', '-' and ':'".into());
+    }
+    if key.starts_with(RESERVED_PREFIX) {
+        return Err(format!("Secret key '{}' is reserved", key));
+    }
+    Ok(())
+}
+
+/// Store a secret in the platform secret store
+#[tauri::command]
+pub(crate) async fn secret_set(
+    app: tauri::AppHandle,
+    key: String,
+    value: String,
+) -> Result<(), String> {
+    check_key(&key)?;
+    let value = Zeroizing::new(value);
+    tauri::async_runtime::spawn_blocking(move || set(&app, &key, &value))
+        .await
+        .map_err(|e| format!("Secret store task failed: {}", e))?
+}
+
+/// Read a secret from the platform secret store (null if not set)
+#[tauri::command]
+pub(crate) async fn secret_get(
+    app: tauri::AppHandle,
+    key: String,
+) -> Result<Option<String>, String> {
+    check_key(&key)?;
+    tauri::async_runtime::spawn_blocking(move || get(&app, &key))
+        .await
+        .map_err(|e| format!("Secret store task failed: {}", e))?
+}
+
+/// Remove a secret from the platform secret store
+#[tauri::command]
+pub(crate) async fn secret_delete(app: tauri::AppHandle, key: String) -> Result<(), String> {
+    check_key(&key)?;
+    tauri::async_runtime::spawn_blocking(move || delete(&app, &key))
+        .await
+        .map_err(|e| format!("Secret store task failed: {}", e))?
+}
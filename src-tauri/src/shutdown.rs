@@ -0,0 +1,176 @@
+// Graceful sidecar shutdown.
+//
+// The backend debounces saves (api/src/services/auto-save.ts) and serializes
+// writes per file, so killing it outright can cut a JSON write off halfway.
+// Stopping the sidecar therefore escalates step by step:
+//   1. POST /api/shutdown - the backend flushes pending writes and exits
+//   2. wait up to `sidecarShutdownTimeoutMs` (setting, default 5s) for the exit
+//   3. SIGTERM (Unix only) - the backend flushes again on the signal
+//   4. CommandChild::kill as a last resort
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tracing::info;
+
+use crate::api_socket::Endpoint;
+use crate::sidecar_status::{self, SidecarPhase};
+use crate::SidecarState;
+
+/// Default time the backend gets to flush and exit after /api/shutdown
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time the backend gets to exit after SIGTERM before it is killed
+#[cfg(unix)]
+const SIGTERM_GRACE: Duration = Duration::from_secs(2);
+
+/// How often the exit is polled while waiting
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Read the shutdown deadline from settings (`sidecarShutdownTimeoutMs`)
+fn shutdown_timeout(app: &tauri::AppHandle) -> Duration {
+    crate::read_setting(app, "sidecarShutdownTimeoutMs")
+        .and_then(|v| v.as_u64())
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT)
+}
+
+/// Whether the sidecar process of `generation` has exited
+fn has_exited(app: &tauri::AppHandle, generation: u64) -> bool {
+    let state = app.state::<Mutex<SidecarState>>();
+    let state_guard = state.lock().unwrap();
+    state_guard.generation != generation || state_guard.pid.is_none()
+}
+
+/// Poll until the sidecar of `generation` exits or `timeout` elapses
+async fn wait_for_exit(app: &tauri::AppHandle, generation: u64, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if has_exited(app, generation) {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(EXIT_POLL_INTERVAL).await;
+    }
+}
+
+/// Ask the backend to flush its pending writes and exit
+pub(crate) async fn request_shutdown(
+    endpoint: &Endpoint,
+    token: &str,
+    timeout: Duration,
+) -> Result<(), String> {
+    let client = crate::api_token::client_with_token(token, endpoint.socket_path(), timeout)?;
+
+    let response = client
+        .post(endpoint.url("/api/shutdown"))
+        .send()
+        .await
+        .map_err(|e| format!("Shutdown request failed: {}", e))?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Shutdown request returned {}", response.status()))
+    }
+}
+
+/// Send SIGTERM so the backend's signal handler can flush before exiting
+#[cfg(unix)]
+fn send_sigterm(pid: u32) -> Result<(), String> {
+    use nix::sys::signal::{kill, Signal};
+    use nix::unistd::Pid;
+
+    kill(Pid::from_raw(pid as i32), Signal::SIGTERM)
+        .map_err(|e| format!("Failed to send SIGTERM to sidecar: {}", e))
+}
+
+/// Force kill the sidecar process of `generation` through its CommandChild handle
+/// Also used by the health monitor to take down a hung backend
+pub(crate) fn force_kill_sidecar(app: &tauri::AppHandle, generation: u64) -> Result<(), String> {
+    let child = {
+        let state = app.state::<Mutex<SidecarState>>();
+        let mut state_guard = state.lock().unwrap();
+        if state_guard.generation != generation {
+            return Ok(());
+        }
+        state_guard.child.take()
+    };
+
+    match child {
+        Some(child) => child
+            .kill()
+            .map_err(|e| format!("Failed to kill sidecar: {}", e)),
+        None => Ok(()),
+    }
+}
+
+/// Stop the sidecar gracefully, escalating to a kill only after the deadline
+/// Returns the PID of the stopped process, or None if no sidecar was running
+pub(crate) async fn shutdown_sidecar(app: &tauri::AppHandle) -> Result<Option<u32>, String> {
+    let (mut pid, mut endpoint, mut generation) = (None, None, 0);
+    sidecar_status::transition(app, SidecarPhase::Stopping, |state| {
+        // Also cancels a supervisor restart that is waiting out its backoff
+        state.stop_requested = true;
+        state.next_restart_at = None;
+        pid = state.pid;
+        endpoint = state.endpoint.clone();
+        generation = state.generation;
+    });
+
+    let Some(pid) = pid else {
+        sidecar_status::transition(app, SidecarPhase::Stopped, |_| {});
+        return Ok(None);
+    };
+
+    let timeout = shutdown_timeout(app);
+    info!(
+        "[Shutdown] Stopping sidecar (PID: {}), deadline {}ms",
+        pid,
+        timeout.as_millis()
+    );
+
+    // 1. Ask the backend to flush and exit on its own
+    match endpoint {
+        Some(endpoint) => {
+            let token = app.state::<crate::api_token::ApiToken>();
+            if let Err(e) = request_shutdown(&endpoint, token.value(), timeout).await {
+                info!("[Shutdown] {}", e);
+            }
+        }
+        None => info!("[Shutdown] Sidecar has no address yet, skipping /api/shutdown"),
+    }
+    if wait_for_exit(app, generation, timeout).await {
+        info!("[Shutdown] Sidecar exited gracefully");
+        return Ok(Some(pid));
+    }
+
+    // 2. Give the signal handler a chance to flush
+    #[cfg(unix)]
+    {
+        info!("[Shutdown] Sidecar still running, sending SIGTERM");
+        match send_sigterm(pid) {
+            Ok(()) => {
+                if wait_for_exit(app, generation, SIGTERM_GRACE).await {
+                    info!("[Shutdown] Sidecar exited after SIGTERM");
+                    return Ok(Some(pid));
+                }
+            }
+            Err(e) => info!("[Shutdown] {}", e),
+        }
+    }
+
+    // 3. Last resort
+    info!("[Shutdown] Sidecar did not exit in time, killing it");
+    force_kill_sidecar(app, generation)?;
+
+    // Clear the PID and address (the Terminated event may never arrive for a killed child)
+    sidecar_status::transition_if_current(app, generation, SidecarPhase::Stopped, |state| {
+        state.pid = None;
+        state.endpoint = None;
+    });
+
+    Ok(Some(pid))
+}
@@ -0,0 +1,179 @@
+// Typed sidecar lifecycle state.
+//
+// Every change to the sidecar's lifecycle goes through transition(), which
+// updates SidecarState and emits a single `sidecar-status-changed` event
+// carrying a full SidecarStatus snapshot. The frontend reads the same
+// snapshot on demand through `get_sidecar_status`.
+//
+// Normal lifecycle:
+//   Stopped -> Starting -> WaitingForPort -> HealthChecking -> Ready
+// (a sidecar on a Unix socket skips WaitingForPort, see api_socket.rs)
+// Ready <-> Degraded is driven by the health monitor, Stopping -> Stopped by
+// an explicit stop, and Failed { reason } by spawn errors, failed readiness
+// checks or the supervisor giving up after a crash loop. DataDirLocked means
+// another instance holds the data directory and the sidecar was not started;
+// VaultLocked means the data directory is encrypted and waits for the passphrase,
+// ChoosingProfile that launch waits for the user to pick a profile.
+
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{Emitter, Manager};
+use tracing::info;
+
+use crate::data_dir_lock::LockInfo;
+use crate::SidecarState;
+
+/// Lifecycle phase of the sidecar process
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub(crate) enum SidecarPhase {
+    /// Process is being spawned
+    Starting,
+    /// Process is running, waiting for the PORT=XXXX line on stdout (TCP fallback only)
+    WaitingForPort,
+    /// Socket or port is known, waiting for /api/health to succeed
+    HealthChecking,
+    /// Backend is answering requests
+    Ready,
+    /// Process is alive but failing health probes
+    Degraded,
+    /// Host asked the process to exit
+    Stopping,
+    /// No process is running
+    #[default]
+    Stopped,
+    /// Sidecar could not be started or kept running
+    Failed { reason: String },
+    /// Another instance holds the data directory lock (see data_dir_lock.rs)
+    DataDirLocked { holder: LockInfo },
+    /// The data directory is an encrypted vault waiting for its passphrase (see vault.rs)
+    VaultLocked,
+    /// Several profiles exist and launch waits for one to be picked (see profiles.rs)
+    ChoosingProfile,
+}
+
+/// Snapshot of the sidecar state sent to the frontend
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SidecarStatus {
+    #[serde(flatten)]
+    pub phase: SidecarPhase,
+    /// Unix timestamp (ms) of the last phase change
+    pub since: u64,
+    /// Unix timestamp (ms) when the current process was spawned
+    pub started_at: Option<u64>,
+    /// Unix timestamp (ms) when the current process passed its readiness check
+    pub ready_at: Option<u64>,
+    /// Unix timestamp (ms) of the next supervisor restart, while waiting out a backoff
+    pub next_restart_at: Option<u64>,
+    pub restart_count: u32,
+    /// Exit code of the most recent process exit
+    pub exit_code: Option<i32>,
+    /// Most recent health probe error (set while Degraded)
+    pub health_error: Option<String>,
+    pub pid: Option<u32>,
+    /// Localhost port, when the sidecar is not on a socket (see api_socket.rs)
+    pub port: Option<u16>,
+    /// Unix socket path the sidecar listens on
+    pub socket: Option<String>,
+    pub data_dir: Option<String>,
+}
+
+/// Current time as a Unix timestamp in milliseconds
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn snapshot(state: &SidecarState) -> SidecarStatus {
+    SidecarStatus {
+        phase: state.phase.clone(),
+        since: state.phase_since,
+        started_at: state.started_at,
+        ready_at: state.ready_at,
+        next_restart_at: state.next_restart_at,
+        restart_count: state.restart_count,
+        exit_code: state.exit_code,
+        health_error: state.health_error.clone(),
+        pid: state.pid,
+        port: state.endpoint.as_ref().and_then(|e| e.port()),
+        socket: state
+            .endpoint
+            .as_ref()
+            .and_then(|e| e.socket_path())
+            .map(|path| path.to_string_lossy().into_owned()),
+        data_dir: state.data_dir.clone(),
+    }
+}
+
+/// Apply `update` to the sidecar state, move to `phase` and emit `sidecar-status-changed`
+pub(crate) fn transition(
+    app: &tauri::AppHandle,
+    phase: SidecarPhase,
+    update: impl FnOnce(&mut SidecarState),
+) {
+    let status = {
+        let state = app.state::<Mutex<SidecarState>>();
+        let mut state_guard = state.lock().unwrap();
+        apply(&mut state_guard, phase, update)
+    };
+    emit(app, status);
+}
+
+/// Same as transition(), but only if `generation` is still the current sidecar
+/// Returns false (and changes nothing) when a newer process has replaced it
+pub(crate) fn transition_if_current(
+    app: &tauri::AppHandle,
+    generation: u64,
+    phase: SidecarPhase,
+    update: impl FnOnce(&mut SidecarState),
+) -> bool {
+    let status = {
+        let state = app.state::<Mutex<SidecarState>>();
+        let mut state_guard = state.lock().unwrap();
+        if state_guard.generation != generation {
+            return false;
+        }
+        apply(&mut state_guard, phase, update)
+    };
+    emit(app, status);
+    true
+}
+
+fn apply(
+    state: &mut SidecarState,
+    phase: SidecarPhase,
+    update: impl FnOnce(&mut SidecarState),
+) -> SidecarStatus {
+    update(state);
+    let now = now_millis();
+    if phase == SidecarPhase::Ready && state.phase != SidecarPhase::Degraded {
+        state.ready_at = Some(now);
+    }
+    if phase == SidecarPhase::Ready {
+        state.health_error = None;
+    }
+    if phase != state.phase {
+        state.phase_since = now;
+    }
+    state.phase = phase;
+    snapshot(state)
+}
+
+fn emit(app: &tauri::AppHandle, status: SidecarStatus) {
+    info!(
+        "[Tauri] Sidecar status: {:?} (PID: {:?}, port: {:?}, socket: {:?})",
+        status.phase, status.pid, status.port, status.socket
+    );
+    let _ = app.emit("sidecar-status-changed", status);
+}
+
+/// Get the current sidecar lifecycle status
+#[tauri::command]
+pub(crate) fn get_sidecar_status(app: tauri::AppHandle) -> SidecarStatus {
+    let state = app.state::<Mutex<SidecarState>>();
+    let state_guard = state.lock().unwrap();
+    snapshot(&state_guard)
+}
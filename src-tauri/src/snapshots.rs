@@ -0,0 +1,865 @@
+// Automatic versioned snapshots of the data directory.
+//
+// A zip snapshot of DATA_DIR (entities/, months/ and data/documents/) is taken
+// on launch and then every `snapshotIntervalMinutes` (default 6 hours), both
+// skipped when nothing changed since the latest snapshot. Snapshots live in
+// `snapshots/` under the app config dir, which is not synced to the cloud, and
+// are pruned per data directory with grandfather-father-son retention: the
+// newest snapshot of each of the last `snapshotKeepDaily` days,
+// `snapshotKeepWeekly` weeks and `snapshotKeepMonthly` months is kept. Manual
+// snapshots are never pruned.
+//
+// Restoring stops the sidecar, stages the snapshot next to the live data,
+// swaps it in with renames and starts the sidecar again. A snapshot of another
+// data directory is only restored when that is asked for explicitly.
+
+use chrono::{Datelike, Local, TimeZone};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, UNIX_EPOCH};
+use tauri::Manager;
+use tracing::{error, info};
+
+use crate::sidecar_status::now_millis;
+use crate::SidecarState;
+
+/// Data directory contents that are snapshotted (relative to DATA_DIR)
+pub(crate) const SNAPSHOT_DIRS: [&str; 3] = ["entities", "months", "data/documents"];
+
+/// Name of the manifest entry stored inside every snapshot archive
+const MANIFEST_NAME: &str = "snapshot.json";
+
+const DEFAULT_INTERVAL_MINUTES: u64 = 6 * 60;
+const DEFAULT_KEEP_DAILY: usize = 7;
+const DEFAULT_KEEP_WEEKLY: usize = 4;
+const DEFAULT_KEEP_MONTHLY: usize = 12;
+
+/// Serializes snapshot creation, deletion and restore
+#[derive(Default)]
+pub(crate) struct SnapshotState {
+    lock: tokio::sync::Mutex<()>,
+}
+
+/// Why a snapshot was taken
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum SnapshotReason {
+    Launch,
+    Scheduled,
+    Manual,
+    PreRestore,
+    PreResolve,
+    PreMigrate,
+}
+
+impl SnapshotReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            SnapshotReason::Launch => "launch",
+            SnapshotReason::Scheduled => "scheduled",
+            SnapshotReason::Manual => "manual",
+            SnapshotReason::PreRestore => "pre-restore",
+            SnapshotReason::PreResolve => "pre-resolve",
+            SnapshotReason::PreMigrate => "pre-migrate",
+        }
+    }
+}
+
+/// Manifest stored inside each snapshot archive
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotManifest {
+    created_at: u64,
+    reason: SnapshotReason,
+    data_dir: String,
+    app_version: String,
+    files: Vec<String>,
+    /// Sizes and modification times of the snapshotted files, used to skip
+    /// scheduled snapshots when nothing changed
+    fingerprint: u64,
+}
+
+/// Snapshot summary returned by `list_snapshots`
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SnapshotInfo {
+    pub id: String,
+    pub created_at: u64,
+    pub reason: SnapshotReason,
+    pub data_dir: String,
+    pub app_version: String,
+    pub file_count: usize,
+    pub size_bytes: u64,
+    #[serde(skip)]
+    fingerprint: u64,
+}
+
+/// Directory holding the snapshot archives
+fn snapshots_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Could not get config dir: {}", e))?;
+    Ok(snapshots_dir_in(&config_dir))
+}
+
+/// Snapshot directory under an app config dir (used by the headless CLI)
+pub(crate) fn snapshots_dir_in(config_dir: &Path) -> PathBuf {
+    config_dir.join("snapshots")
+}
+
+/// Data directory of the running sidecar, else the saved one, else the default
+pub(crate) fn current_data_dir(app: &tauri::AppHandle) -> Result<String, String> {
+    let running = {
+        let state = app.state::<Mutex<SidecarState>>();
+        let state_guard = state.lock().unwrap();
+        state_guard.data_dir.clone()
+    };
+    crate::resolve_data_dir(running.or_else(|| crate::read_saved_data_dir(app)))
+}
+
+/// Snapshot ids are archive file stems; reject anything that could escape the folder
+fn snapshot_path(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid snapshot id: {}", id));
+    }
+    let path = dir.join(format!("{}.zip", id));
+    if !path.exists() {
+        return Err(format!("Snapshot not found: {}", id));
+    }
+    Ok(path)
+}
+
+/// Collect every file under the snapshotted directories as (absolute, relative) paths
+pub(crate) fn collect_data_files(data_dir: &Path) -> Vec<(PathBuf, String)> {
+    fn walk(dir: &Path, rel: &str, out: &mut Vec<(PathBuf, String)>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let rel_path = format!("{}/{}", rel, name);
+            if path.is_dir() {
+                walk(&path, &rel_path, out);
+            } else if path.is_file() && !name.contains(".tmp-") {
+                // Skip in-flight atomic writes from the backend
+                out.push((path, rel_path));
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    for subdir in SNAPSHOT_DIRS {
+        walk(&data_dir.join(subdir), subdir, &mut files);
+    }
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+    files
+}
+
+/// Cheap change detector over file names, sizes and modification times
+fn fingerprint(files: &[(PathBuf, String)]) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for (path, rel) in files {
+        rel.hash(&mut hasher);
+        if let Ok(meta) = std::fs::metadata(path) {
+            meta.len().hash(&mut hasher);
+            meta.modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos())
+                .hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+fn read_manifest(path: &Path) -> Result<SnapshotManifest, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open snapshot: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Invalid snapshot archive: {}", e))?;
+    let mut entry = archive
+        .by_name(MANIFEST_NAME)
+        .map_err(|e| format!("Snapshot has no manifest: {}", e))?;
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .map_err(|e| format!("Failed to read snapshot manifest: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid snapshot manifest: {}", e))
+}
+
+/// Read every snapshot in `dir`, newest first (unreadable archives are skipped)
+fn read_snapshots(dir: &Path) -> Vec<SnapshotInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut snapshots: Vec<SnapshotInfo> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "zip"))
+        .filter_map(|path| {
+            let id = path.file_stem()?.to_string_lossy().to_string();
+            let manifest = match read_manifest(&path) {
+                Ok(m) => m,
+                Err(e) => {
+                    info!("[Snapshots] Skipping {:?}: {}", path, e);
+                    return None;
+                }
+            };
+            Some(SnapshotInfo {
+                id,
+                created_at: manifest.created_at,
+                reason: manifest.reason,
+                data_dir: manifest.data_dir,
+                app_version: manifest.app_version,
+                file_count: manifest.files.len(),
+                size_bytes: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+                fingerprint: manifest.fingerprint,
+            })
+        })
+        .collect();
+
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+    snapshots
+}
+
+/// Whether the archive at `path` has every file in `files` and each passes `valid`
+fn has_valid_copies(path: &Path, files: &[String], valid: &impl Fn(&str, &[u8]) -> bool) -> bool {
+    let Some(mut archive) = File::open(path)
+        .ok()
+        .and_then(|file| zip::ZipArchive::new(file).ok())
+    else {
+        return false;
+    };
+    files.iter().all(|rel| {
+        let Ok(mut entry) = archive.by_name(rel) else {
+            return false;
+        };
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).is_ok() && valid(rel, &content)
+    })
+}
+
+/// Newest snapshot of `data_dir` with a copy of every file in `files` that passes
+/// `valid` (offered as a restore point when the launch integrity check fails)
+pub(crate) fn newest_snapshot_with(
+    app: &tauri::AppHandle,
+    data_dir: &str,
+    files: &[String],
+    valid: impl Fn(&str, &[u8]) -> bool,
+) -> Option<String> {
+    let dir = snapshots_dir(app).ok()?;
+    read_snapshots(&dir)
+        .into_iter()
+        .filter(|snapshot| Path::new(&snapshot.data_dir) == Path::new(data_dir))
+        .find(|snapshot| has_valid_copies(&dir.join(format!("{}.zip", snapshot.id)), files, &valid))
+        .map(|snapshot| snapshot.id)
+}
+
+/// Write a snapshot archive of `data_dir` into `dir`
+pub(crate) fn write_snapshot(
+    dir: &Path,
+    data_dir: &Path,
+    reason: SnapshotReason,
+    app_version: &str,
+) -> Result<SnapshotInfo, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create snapshots directory: {}", e))?;
+
+    let created_at = now_millis();
+    let timestamp = Local
+        .timestamp_millis_opt(created_at as i64)
+        .single()
+        .unwrap_or_else(Local::now)
+        .format("%Y%m%d-%H%M%S");
+    let id = format!("{}-{}", timestamp, reason.as_str());
+    let final_path = dir.join(format!("{}.zip", id));
+    let temp_path = dir.join(format!("{}.zip.tmp", id));
+
+    let files = collect_data_files(data_dir);
+    let manifest = SnapshotManifest {
+        created_at,
+        reason,
+        data_dir: data_dir.to_string_lossy().to_string(),
+        app_version: app_version.to_string(),
+        files: files.iter().map(|(_, rel)| rel.clone()).collect(),
+        fingerprint: fingerprint(&files),
+    };
+
+    let result = (|| -> Result<(), String> {
+        let file = File::create(&temp_path)
+            .map_err(|e| format!("Failed to create snapshot file: {}", e))?;
+        let mut zip = zip::ZipWriter::new(file);
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+
+        for (path, rel) in &files {
+            zip.start_file(rel.as_str(), options)
+                .map_err(|e| format!("Failed to add {} to snapshot: {}", rel, e))?;
+            let mut source =
+                File::open(path).map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+            std::io::copy(&mut source, &mut zip)
+                .map_err(|e| format!("Failed to add {} to snapshot: {}", rel, e))?;
+        }
+
+        let manifest_json = serde_json::to_vec_pretty(&manifest)
+            .map_err(|e| format!("Failed to serialize snapshot manifest: {}", e))?;
+        zip.start_file(MANIFEST_NAME, options)
+            .map_err(|e| format!("Failed to write snapshot manifest: {}", e))?;
+        zip.write_all(&manifest_json)
+            .map_err(|e| format!("Failed to write snapshot manifest: {}", e))?;
+
+        let file = zip
+            .finish()
+            .map_err(|e| format!("Failed to finish snapshot: {}", e))?;
+        file.sync_all()
+            .map_err(|e| format!("Failed to flush snapshot: {}", e))?;
+        std::fs::rename(&temp_path, &final_path)
+            .map_err(|e| format!("Failed to save snapshot: {}", e))
+    })();
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    info!(
+        "[Snapshots] Created {} ({} files from {:?})",
+        id,
+        files.len(),
+        data_dir
+    );
+
+    Ok(SnapshotInfo {
+        id,
+        created_at,
+        reason,
+        data_dir: manifest.data_dir,
+        app_version: manifest.app_version,
+        file_count: files.len(),
+        size_bytes: std::fs::metadata(&final_path).map(|m| m.len()).unwrap_or(0),
+        fingerprint: manifest.fingerprint,
+    })
+}
+
+/// Retention limits read from settings
+struct Retention {
+    daily: usize,
+    weekly: usize,
+    monthly: usize,
+}
+
+impl Retention {
+    fn from_settings(app: &tauri::AppHandle) -> Self {
+        let read = |key: &str, default: usize| {
+            crate::read_setting(app, key)
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize)
+                .unwrap_or(default)
+        };
+        Retention {
+            daily: read("snapshotKeepDaily", DEFAULT_KEEP_DAILY),
+            weekly: read("snapshotKeepWeekly", DEFAULT_KEEP_WEEKLY),
+            monthly: read("snapshotKeepMonthly", DEFAULT_KEEP_MONTHLY),
+        }
+    }
+}
+
+/// Pick the snapshots that fall outside the retention rules
+/// Each data directory keeps its own snapshots; `snapshots` must be sorted newest first.
+fn snapshots_to_prune(snapshots: &[SnapshotInfo], retention: &Retention) -> Vec<String> {
+    let mut groups: Vec<(&Path, Vec<&SnapshotInfo>)> = Vec::new();
+    for snapshot in snapshots {
+        let data_dir = Path::new(&snapshot.data_dir);
+        match groups.iter_mut().find(|(dir, _)| *dir == data_dir) {
+            Some((_, group)) => group.push(snapshot),
+            None => groups.push((data_dir, vec![snapshot])),
+        }
+    }
+
+    let keep: HashSet<&str> = groups
+        .iter()
+        .flat_map(|(_, group)| retained(group, retention))
+        .collect();
+    snapshots
+        .iter()
+        .filter(|s| !keep.contains(s.id.as_str()))
+        .map(|s| s.id.clone())
+        .collect()
+}
+
+/// Ids of the snapshots of one data directory kept by the retention rules
+fn retained<'a>(snapshots: &[&'a SnapshotInfo], retention: &Retention) -> HashSet<&'a str> {
+    let mut keep: HashSet<&str> = HashSet::new();
+
+    // Always keep the newest snapshot
+    if let Some(newest) = snapshots.first() {
+        keep.insert(&newest.id);
+    }
+
+    let mut days = Vec::new();
+    let mut weeks = Vec::new();
+    let mut months = Vec::new();
+
+    for snapshot in snapshots {
+        // Manual snapshots are only removed by the user
+        if snapshot.reason == SnapshotReason::Manual {
+            keep.insert(&snapshot.id);
+            continue;
+        }
+
+        let Some(date) = Local
+            .timestamp_millis_opt(snapshot.created_at as i64)
+            .single()
+            .map(|dt| dt.date_naive())
+        else {
+            continue;
+        };
+
+        // The first snapshot seen for a period is the newest one in it
+        let day = (date.year(), date.ordinal());
+        if !days.contains(&day) && days.len() < retention.daily {
+            days.push(day);
+            keep.insert(&snapshot.id);
+        }
+
+        let week = (date.iso_week().year(), date.iso_week().week());
+        if !weeks.contains(&week) && weeks.len() < retention.weekly {
+            weeks.push(week);
+            keep.insert(&snapshot.id);
+        }
+
+        let month = (date.year(), date.month());
+        if !months.contains(&month) && months.len() < retention.monthly {
+            months.push(month);
+            keep.insert(&snapshot.id);
+        }
+    }
+    keep
+}
+
+fn prune(dir: &Path, retention: &Retention) {
+    let snapshots = read_snapshots(dir);
+    for id in snapshots_to_prune(&snapshots, retention) {
+        match std::fs::remove_file(dir.join(format!("{}.zip", id))) {
+            Ok(()) => info!("[Snapshots] Pruned {}", id),
+            Err(e) => info!("[Snapshots] Failed to prune {}: {}", id, e),
+        }
+    }
+}
+
+/// Take a snapshot of `data_dir` and apply retention
+/// Launch and scheduled snapshots are skipped (returning None) when nothing changed
+/// since the latest one
+pub(crate) async fn take_snapshot(
+    app: &tauri::AppHandle,
+    data_dir: String,
+    reason: SnapshotReason,
+) -> Result<Option<SnapshotInfo>, String> {
+    let state = app.state::<SnapshotState>();
+    let _guard = state.lock.lock().await;
+
+    let dir = snapshots_dir(app)?;
+    let retention = Retention::from_settings(app);
+    let app_version = app.package_info().version.to_string();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let data_path = PathBuf::from(&data_dir);
+
+        if matches!(reason, SnapshotReason::Launch | SnapshotReason::Scheduled) {
+            let current = fingerprint(&collect_data_files(&data_path));
+            let unchanged = read_snapshots(&dir)
+                .iter()
+                .find(|s| s.data_dir == data_dir)
+                .is_some_and(|latest| latest.fingerprint == current);
+            if unchanged {
+                return Ok(None);
+            }
+        }
+
+        let info = write_snapshot(&dir, &data_path, reason, &app_version)?;
+        prune(&dir, &retention);
+        Ok(Some(info))
+    })
+    .await
+    .map_err(|e| format!("Snapshot task failed: {}", e))?
+}
+
+/// Take snapshots on a schedule for the lifetime of the app
+pub(crate) fn spawn_scheduler(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let minutes = crate::read_setting(&app, "snapshotIntervalMinutes")
+                .and_then(|v| v.as_u64())
+                .filter(|m| *m > 0)
+                .unwrap_or(DEFAULT_INTERVAL_MINUTES);
+            tokio::time::sleep(Duration::from_secs(minutes * 60)).await;
+
+            let data_dir = match current_data_dir(&app) {
+                Ok(dir) => dir,
+                Err(e) => {
+                    info!("[Snapshots] Skipping scheduled snapshot: {}", e);
+                    continue;
+                }
+            };
+            match take_snapshot(&app, data_dir, SnapshotReason::Scheduled).await {
+                Ok(Some(_)) => {}
+                Ok(None) => info!("[Snapshots] No changes since last snapshot"),
+                Err(e) => error!("[Snapshots] Scheduled snapshot failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Extract `snapshot` into `staging`, optionally limited to `only` (relative paths)
+fn extract_snapshot(
+    snapshot: &Path,
+    staging: &Path,
+    only: Option<&HashSet<String>>,
+) -> Result<Vec<String>, String> {
+    let file = File::open(snapshot).map_err(|e| format!("Failed to open snapshot: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Invalid snapshot archive: {}", e))?;
+
+    let mut extracted = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read snapshot entry: {}", e))?;
+        if entry.is_dir() || entry.name() == MANIFEST_NAME {
+            continue;
+        }
+        // enclosed_name() rejects absolute paths and `..` components
+        let Some(rel) = entry.enclosed_name() else {
+            return Err(format!(
+                "Snapshot contains an unsafe path: {}",
+                entry.name()
+            ));
+        };
+        let rel_str = rel.to_string_lossy().replace('\\', "/");
+        if !SNAPSHOT_DIRS
+            .iter()
+            .any(|d| rel_str.starts_with(&format!("{}/", d)))
+        {
+            continue;
+        }
+        if only.is_some_and(|only| !only.contains(&rel_str)) {
+            continue;
+        }
+
+        let target = staging.join(&rel);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        let mut out =
+            File::create(&target).map_err(|e| format!("Failed to write {}: {}", rel_str, e))?;
+        std::io::copy(&mut entry, &mut out)
+            .map_err(|e| format!("Failed to write {}: {}", rel_str, e))?;
+        out.sync_all()
+            .map_err(|e| format!("Failed to flush {}: {}", rel_str, e))?;
+        extracted.push(rel_str);
+    }
+    Ok(extracted)
+}
+
+/// Swap the staged snapshot contents into `data_dir`
+/// Full restores swap whole directories; partial restores rename single files into place.
+/// Both use renames within the data directory, so each swap is atomic.
+fn swap_in(staging: &Path, data_dir: &Path, files: Option<&[String]>) -> Result<(), String> {
+    if let Some(files) = files {
+        for rel in files {
+            let target = data_dir.join(rel);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+            }
+            std::fs::rename(staging.join(rel), &target)
+                .map_err(|e| format!("Failed to restore {}: {}", rel, e))?;
+        }
+        return Ok(());
+    }
+
+    let previous = staging.join(".previous");
+    let mut swapped: Vec<&str> = Vec::new();
+
+    let result = (|| -> Result<(), String> {
+        for subdir in SNAPSHOT_DIRS {
+            let live = data_dir.join(subdir);
+            let staged = staging.join(subdir);
+            let backup = previous.join(subdir);
+            if let Some(parent) = backup.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to prepare restore: {}", e))?;
+            }
+            if live.exists() {
+                std::fs::rename(&live, &backup)
+                    .map_err(|e| format!("Failed to move aside {}: {}", subdir, e))?;
+            }
+            swapped.push(subdir);
+            if staged.exists() {
+                if let Some(parent) = live.parent() {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|e| format!("Failed to prepare restore: {}", e))?;
+                }
+                std::fs::rename(&staged, &live)
+                    .map_err(|e| format!("Failed to restore {}: {}", subdir, e))?;
+            } else {
+                std::fs::create_dir_all(&live)
+                    .map_err(|e| format!("Failed to recreate {}: {}", subdir, e))?;
+            }
+        }
+        Ok(())
+    })();
+
+    // Roll back the directories that were already swapped
+    if result.is_err() {
+        for subdir in swapped {
+            let live = data_dir.join(subdir);
+            let backup = previous.join(subdir);
+            if backup.exists() {
+                let _ = std::fs::remove_dir_all(&live);
+                let _ = std::fs::rename(&backup, &live);
+            }
+        }
+    }
+    result
+}
+
+/// Restore `id` (or only `files` from it) into `data_dir`
+fn restore_files(
+    snapshot: &Path,
+    data_dir: &Path,
+    files: Option<Vec<String>>,
+) -> Result<usize, String> {
+    let staging = data_dir.join(format!(".restore-{}", now_millis()));
+    std::fs::create_dir_all(&staging)
+        .map_err(|e| format!("Failed to create restore staging directory: {}", e))?;
+
+    let only: Option<HashSet<String>> = files.map(|f| f.into_iter().collect());
+    let result = extract_snapshot(snapshot, &staging, only.as_ref()).and_then(|extracted| {
+        if let Some(only) = &only {
+            let missing: Vec<&String> = only.iter().filter(|f| !extracted.contains(f)).collect();
+            if !missing.is_empty() {
+                return Err(format!("Files not found in snapshot: {:?}", missing));
+            }
+        }
+        let partial = only.as_ref().map(|_| extracted.as_slice());
+        swap_in(&staging, data_dir, partial)?;
+        Ok(extracted.len())
+    });
+
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+/// List all snapshots, newest first
+#[tauri::command]
+pub(crate) async fn list_snapshots(app: tauri::AppHandle) -> Result<Vec<SnapshotInfo>, String> {
+    let dir = snapshots_dir(&app)?;
+    tauri::async_runtime::spawn_blocking(move || read_snapshots(&dir))
+        .await
+        .map_err(|e| format!("Failed to list snapshots: {}", e))
+}
+
+/// Take a manual snapshot of the current data directory
+#[tauri::command]
+pub(crate) async fn create_snapshot(app: tauri::AppHandle) -> Result<SnapshotInfo, String> {
+    let data_dir = current_data_dir(&app)?;
+    take_snapshot(&app, data_dir, SnapshotReason::Manual)
+        .await?
+        .ok_or("Snapshot was not created".to_string())
+}
+
+/// Delete a snapshot
+#[tauri::command]
+pub(crate) async fn delete_snapshot(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    let state = app.state::<SnapshotState>();
+    let _guard = state.lock.lock().await;
+
+    let path = snapshot_path(&snapshots_dir(&app)?, &id)?;
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to delete snapshot: {}", e))?;
+    info!("[Snapshots] Deleted {}", id);
+    Ok(())
+}
+
+/// Restore a snapshot into the current data directory
+/// Pass `files` (e.g. `["months/2026-01.json"]`) to restore only those files.
+/// A snapshot of another data directory is refused unless `allow_other_data_dir` is set.
+/// The sidecar is stopped for the swap and started again afterwards.
+#[tauri::command]
+pub(crate) async fn restore_snapshot(
+    app: tauri::AppHandle,
+    id: String,
+    files: Option<Vec<String>>,
+    allow_other_data_dir: Option<bool>,
+) -> Result<String, String> {
+    let snapshot = snapshot_path(&snapshots_dir(&app)?, &id)?;
+    let data_dir = current_data_dir(&app)?;
+
+    let manifest = read_manifest(&snapshot)?;
+    if Path::new(&manifest.data_dir) != Path::new(&data_dir) && allow_other_data_dir != Some(true) {
+        return Err(format!(
+            "Snapshot {} was taken of {}, not the current data directory {}",
+            id, manifest.data_dir, data_dir
+        ));
+    }
+
+    // Keep the current state so the restore itself can be undone
+    take_snapshot(&app, data_dir.clone(), SnapshotReason::PreRestore).await?;
+
+    let state = app.state::<SnapshotState>();
+    let guard = state.lock.lock().await;
+
+    crate::shutdown::shutdown_sidecar(&app).await?;
+    // Save an unlocked vault's working copy; the restart decrypts the restored files
+    crate::vault::close(&app);
+
+    let target = PathBuf::from(&data_dir);
+    let restored =
+        tauri::async_runtime::spawn_blocking(move || restore_files(&snapshot, &target, files))
+            .await
+            .map_err(|e| format!("Restore task failed: {}", e));
+    drop(guard);
+
+    // Start the sidecar again even if the restore failed, so the app keeps working
+    crate::supervisor::reset(&app);
+    let started = crate::start_bun_sidecar_internal(&app, Some(data_dir)).await;
+    if started.is_ok() {
+        let _ = crate::wait_for_sidecar_ready(&app).await;
+    }
+
+    let count = restored??;
+    started?;
+    info!("[Snapshots] Restored {} files from {}", count, id);
+    Ok(format!("Restored {} files from snapshot {}", count, id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (case, (daily, weekly, monthly), snapshots newest first, expected pruned)
+    type Case = (
+        &'static str,
+        (usize, usize, usize),
+        Vec<(&'static str, &'static str, SnapshotReason)>,
+        Vec<&'static str>,
+    );
+
+    /// A snapshot of `data_dir` taken at noon on `date` (YYYY-MM-DD)
+    fn snapshot(data_dir: &str, date: &str, reason: SnapshotReason) -> SnapshotInfo {
+        let day = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        let created_at = Local
+            .from_local_datetime(&day.and_hms_opt(12, 0, 0).unwrap())
+            .single()
+            .unwrap()
+            .timestamp_millis() as u64;
+        SnapshotInfo {
+            id: format!("{}-{}", data_dir, date),
+            created_at,
+            reason,
+            data_dir: data_dir.to_string(),
+            app_version: "0.0.0".to_string(),
+            file_count: 0,
+            size_bytes: 0,
+            fingerprint: 0,
+        }
+    }
+
+    #[test]
+    fn snapshots_to_prune_applies_retention() {
+        use SnapshotReason::{Launch, Manual, Scheduled};
+
+        let cases: Vec<Case> = vec![
+            (
+                "daily keeps the newest of each day",
+                (2, 0, 0),
+                vec![
+                    ("/a", "2026-03-10", Scheduled),
+                    ("/a", "2026-03-09", Launch),
+                    ("/a", "2026-03-08", Scheduled),
+                ],
+                vec!["/a-2026-03-08"],
+            ),
+            (
+                "weekly keeps the newest of each ISO week",
+                (0, 2, 0),
+                vec![
+                    ("/a", "2026-03-11", Scheduled),
+                    ("/a", "2026-03-10", Scheduled),
+                    ("/a", "2026-03-04", Scheduled),
+                    ("/a", "2026-02-25", Scheduled),
+                ],
+                vec!["/a-2026-03-10", "/a-2026-02-25"],
+            ),
+            (
+                "monthly keeps the newest of each month",
+                (0, 0, 2),
+                vec![
+                    ("/a", "2026-03-20", Scheduled),
+                    ("/a", "2026-03-01", Scheduled),
+                    ("/a", "2026-02-15", Scheduled),
+                    ("/a", "2026-01-15", Scheduled),
+                ],
+                vec!["/a-2026-03-01", "/a-2026-01-15"],
+            ),
+            (
+                "buckets combine",
+                (1, 1, 2),
+                vec![
+                    ("/a", "2026-03-20", Scheduled),
+                    ("/a", "2026-03-19", Scheduled),
+                    ("/a", "2026-03-01", Scheduled),
+                    ("/a", "2026-02-15", Scheduled),
+                    ("/a", "2026-01-15", Scheduled),
+                ],
+                vec!["/a-2026-03-19", "/a-2026-03-01", "/a-2026-01-15"],
+            ),
+            (
+                "manual snapshots and the newest one are never pruned",
+                (0, 0, 0),
+                vec![
+                    ("/a", "2026-03-10", Scheduled),
+                    ("/a", "2026-03-09", Manual),
+                    ("/a", "2026-03-08", Scheduled),
+                ],
+                vec!["/a-2026-03-08"],
+            ),
+            (
+                "each data directory keeps its own snapshots",
+                (1, 0, 0),
+                vec![
+                    ("/a", "2026-03-10", Scheduled),
+                    ("/b", "2026-03-09", Scheduled),
+                    ("/a", "2026-03-08", Scheduled),
+                    ("/b", "2026-03-07", Scheduled),
+                ],
+                vec!["/a-2026-03-08", "/b-2026-03-07"],
+            ),
+        ];
+
+        for (case, (daily, weekly, monthly), snapshots, expected) in cases {
+            let snapshots: Vec<SnapshotInfo> = snapshots
+                .into_iter()
+                .map(|(dir, date, reason)| snapshot(dir, date, reason))
+                .collect();
+            let retention = Retention {
+                daily,
+                weekly,
+                monthly,
+            };
+            assert_eq!(
+                snapshots_to_prune(&snapshots, &retention),
+                expected,
+                "{}",
+                case
+            );
+        }
+    }
+}
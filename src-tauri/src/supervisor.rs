@@ -0,0 +1,179 @@
+// Sidecar supervisor: restarts the Bun backend when it exits unexpectedly.
+//
+// When the sidecar terminates without a stop being requested (stop_bun_sidecar,
+// restart_bun_sidecar or app shutdown), the supervisor respawns it against the
+// same DATA_DIR after an exponential backoff and re-runs the /api/health
+// readiness check. If the sidecar keeps crashing (MAX_CRASHES within
+// CRASH_WINDOW) it gives up and reports a crash-loop state to the frontend.
+//
+// Progress is reported through the sidecar status: while waiting out the
+// backoff the phase is Stopped with `nextRestartAt` set, and a crash loop
+// ends in Failed { reason }.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tracing::{error, info};
+
+use crate::sidecar_status::{self, SidecarPhase};
+use crate::SidecarState;
+
+/// Delay before the first restart attempt, doubled for each crash in the window
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Upper bound for the restart delay
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Number of crashes within CRASH_WINDOW that counts as a crash loop
+const MAX_CRASHES: usize = 5;
+
+/// Sliding window used to count recent crashes
+const CRASH_WINDOW: Duration = Duration::from_secs(120);
+
+/// Supervisor bookkeeping, managed as `Mutex<SupervisorState>`
+#[derive(Default)]
+pub(crate) struct SupervisorState {
+    crash_times: VecDeque<Instant>,
+}
+
+/// Compute the backoff delay for the given number of crashes in the current window
+fn backoff_delay(crashes_in_window: usize) -> Duration {
+    let exponent = crashes_in_window.saturating_sub(1).min(16) as u32;
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.pow(exponent))
+        .min(MAX_BACKOFF)
+}
+
+/// Clear crash history (called when the user explicitly starts or restarts the sidecar)
+pub(crate) fn reset(app: &tauri::AppHandle) {
+    let state = app.state::<Mutex<SupervisorState>>();
+    let mut state_guard = state.lock().unwrap();
+    state_guard.crash_times.clear();
+}
+
+/// Handle a sidecar exit that was not requested by the host
+/// Records the crash and either schedules a restart or enters the crash-loop state
+pub(crate) fn handle_unexpected_exit(
+    app: &tauri::AppHandle,
+    generation: u64,
+    exit_code: Option<i32>,
+) {
+    let now = Instant::now();
+
+    let crashes = {
+        let state = app.state::<Mutex<SupervisorState>>();
+        let mut state_guard = state.lock().unwrap();
+
+        state_guard.crash_times.push_back(now);
+        while let Some(first) = state_guard.crash_times.front() {
+            if now.duration_since(*first) > CRASH_WINDOW {
+                state_guard.crash_times.pop_front();
+            } else {
+                break;
+            }
+        }
+        state_guard.crash_times.len()
+    };
+
+    if crashes >= MAX_CRASHES {
+        info!(
+            "[Supervisor] Sidecar crashed {} times in {}s, giving up",
+            crashes,
+            CRASH_WINDOW.as_secs()
+        );
+        sidecar_status::transition_if_current(
+            app,
+            generation,
+            SidecarPhase::Failed {
+                reason: format!(
+                    "Backend crashed {} times in {} seconds and was not restarted",
+                    crashes,
+                    CRASH_WINDOW.as_secs()
+                ),
+            },
+            |state| {
+                state.child = None;
+                state.pid = None;
+                state.endpoint = None;
+                state.exit_code = exit_code;
+                state.next_restart_at = None;
+            },
+        );
+        return;
+    }
+
+    let delay = backoff_delay(crashes);
+    let mut attempt = 0;
+    let current =
+        sidecar_status::transition_if_current(app, generation, SidecarPhase::Stopped, |state| {
+            state.child = None;
+            state.pid = None;
+            state.endpoint = None;
+            state.exit_code = exit_code;
+            state.restart_count += 1;
+            attempt = state.restart_count;
+            state.next_restart_at = Some(sidecar_status::now_millis() + delay.as_millis() as u64);
+        });
+    if !current {
+        return;
+    }
+
+    info!(
+        "[Supervisor] Sidecar exited unexpectedly (code: {:?}), restarting in {}ms (attempt {})",
+        exit_code,
+        delay.as_millis(),
+        attempt
+    );
+    schedule_restart(app.clone(), generation, delay);
+}
+
+fn is_data_dir_locked(app: &tauri::AppHandle) -> bool {
+    let state = app.state::<Mutex<SidecarState>>();
+    let state_guard = state.lock().unwrap();
+    matches!(state_guard.phase, SidecarPhase::DataDirLocked { .. })
+}
+
+/// Respawn the sidecar after `delay` using the DATA_DIR of the process that exited
+fn schedule_restart(app: tauri::AppHandle, generation: u64, delay: Duration) {
+    // The crashed generation is checked again after the delay so a manual
+    // start/stop during the backoff period cancels this restart
+    let data_dir = {
+        let state = app.state::<Mutex<SidecarState>>();
+        let state_guard = state.lock().unwrap();
+        state_guard.data_dir.clone()
+    };
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+
+        let cancelled = {
+            let state = app.state::<Mutex<SidecarState>>();
+            let state_guard = state.lock().unwrap();
+            state_guard.stop_requested || state_guard.generation != generation
+        };
+        if cancelled {
+            info!("[Supervisor] Restart cancelled (sidecar was stopped or restarted manually)");
+            return;
+        }
+
+        match crate::start_bun_sidecar_internal(&app, data_dir).await {
+            Ok(msg) => {
+                info!("[Supervisor] {}", msg);
+                match crate::wait_for_sidecar_ready(&app).await {
+                    Ok(endpoint) => info!("[Supervisor] Sidecar is ready again on {}", endpoint),
+                    Err(e) => info!("[Supervisor] Restarted sidecar failed health check: {}", e),
+                }
+            }
+            Err(e) => {
+                error!("[Supervisor] Failed to respawn sidecar: {}", e);
+                // Another instance took the data directory; retrying would not help
+                if is_data_dir_locked(&app) {
+                    return;
+                }
+                // A failed spawn counts as another crash so backoff and crash-loop still apply
+                handle_unexpected_exit(&app, generation, None);
+            }
+        }
+    });
+}
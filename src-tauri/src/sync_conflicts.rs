@@ -0,0 +1,677 @@
+// Cloud-sync conflict copies in the data directory.
+//
+// When two devices edit the same file before syncing, sync tools keep both
+// versions side by side instead of merging them:
+//   Dropbox       `2026-01 (conflicted copy).json`, `bills (Jane's conflicted copy 2026-01-02).json`
+//   Syncthing     `bills.sync-conflict-20260102-101500-ABCDEFG.json`
+//   Google Drive  `bills (1).json`
+//   iCloud        `bills 2.json`, and `.bills.json.icloud` for a file not downloaded yet
+// The backend only reads the original name, so the other device's changes
+// are silently lost. `list_sync_conflicts` finds these copies in `entities/`
+// and `months/` and diffs both versions record by record (keyed by `id`:
+// bills, bill_instances, occurrences, payments, ...). `resolve_sync_conflict`
+// keeps one side or merges both. Without a common ancestor a record on only
+// one side may be new there or deleted on the other side, so a merge only
+// keeps the one-sided records the user picked.
+
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::snapshots::{self, SnapshotReason};
+
+/// Subdirectories that are searched for conflict copies
+const CONFLICT_DIRS: [&str; 2] = ["entities", "months"];
+
+/// Fields ignored when comparing records (they change on every save)
+const IGNORED_FIELDS: [&str; 1] = ["updated_at"];
+
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ConflictKind {
+    /// A second version of the file written by the sync tool
+    ConflictCopy,
+    /// An iCloud placeholder for a file that has not been downloaded
+    NotDownloaded,
+}
+
+/// How a record differs between the local file and the conflict copy
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum RecordChange {
+    /// Only in the local file (added here, or deleted on the other device)
+    OnlyLocal,
+    /// Only in the conflict copy (added on the other device, or deleted here)
+    OnlyConflict,
+    /// In both, with different values
+    Changed,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RecordDiff {
+    /// Record path, e.g. `bill_instances/abc/occurrences/def`
+    /// Empty for the top-level fields of a month file
+    pub path: String,
+    pub change: RecordChange,
+}
+
+/// A conflict copy returned by `list_sync_conflicts`
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SyncConflict {
+    /// The conflict copy, relative to the data directory (identifies the conflict)
+    pub conflict_file: String,
+    /// The file it conflicts with, e.g. `months/2026-01.json`
+    pub file: String,
+    pub kind: ConflictKind,
+    pub local_exists: bool,
+    /// Set when either version could not be parsed (only keeping a side is possible)
+    pub error: Option<String>,
+    pub differences: Vec<RecordDiff>,
+    /// Whether `merge` can resolve this conflict (no record changed on both sides)
+    /// Records on only one side are kept only when listed in `keep_records`.
+    pub can_merge: bool,
+}
+
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum SyncResolution {
+    /// Keep the local file and discard the conflict copy
+    KeepLocal,
+    /// Replace the local file with the conflict copy
+    KeepConflict,
+    /// Combine the records of both versions, keeping the chosen one-sided records
+    Merge,
+}
+
+/// If `name` is a conflict copy, return the file name it conflicts with
+/// `existing` holds the other file names in the same directory; the looser
+/// patterns (`bills (1).json`, `bills 2.json`) only match when the original exists
+fn conflict_original(name: &str, existing: &HashSet<String>) -> Option<(String, ConflictKind)> {
+    if let Some(original) = name
+        .strip_prefix('.')
+        .and_then(|n| n.strip_suffix(".icloud"))
+    {
+        return original
+            .ends_with(".json")
+            .then(|| (original.to_string(), ConflictKind::NotDownloaded));
+    }
+
+    let stem = name.strip_suffix(".json")?;
+    let copy = |base: &str| Some((format!("{}.json", base), ConflictKind::ConflictCopy));
+    let original_exists = |base: &str| existing.contains(&format!("{}.json", base));
+
+    if let Some((base, _)) = stem.split_once(".sync-conflict-") {
+        return copy(base);
+    }
+
+    if let Some((base, inner)) = stem.strip_suffix(')').and_then(|s| s.rsplit_once(" (")) {
+        if inner.to_lowercase().contains("conflict")
+            || (inner.chars().all(|c| c.is_ascii_digit()) && original_exists(base))
+        {
+            return copy(base);
+        }
+    }
+
+    if let Some((base, number)) = stem.rsplit_once(' ') {
+        if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) && original_exists(base)
+        {
+            return copy(base);
+        }
+    }
+
+    None
+}
+
+/// Whether `file` (relative to the data directory) is a conflict copy
+pub(crate) fn is_conflict_copy(data_dir: &Path, file: &str) -> bool {
+    let Some((dir, name)) = file.split_once('/') else {
+        return false;
+    };
+    let existing: HashSet<String> = std::fs::read_dir(data_dir.join(dir))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    conflict_original(name, &existing).is_some()
+}
+
+/// Whether every item is a record (an object with a string `id`)
+fn is_records(items: &[Value]) -> bool {
+    items
+        .iter()
+        .all(|item| item.get("id").is_some_and(|id| id.is_string()))
+}
+
+fn is_record_array(value: &Value) -> bool {
+    value.as_array().is_some_and(|items| is_records(items))
+}
+
+fn record_id(record: &Value) -> &str {
+    record
+        .get("id")
+        .and_then(|id| id.as_str())
+        .unwrap_or_default()
+}
+
+fn join_path(parent: &str, child: &str) -> String {
+    if parent.is_empty() {
+        child.to_string()
+    } else {
+        format!("{}/{}", parent, child)
+    }
+}
+
+/// The fields of a record that belong to it (not nested record lists or ignored fields)
+fn own_fields(record: &Map<String, Value>) -> Map<String, Value> {
+    record
+        .iter()
+        .filter(|(key, value)| !IGNORED_FIELDS.contains(&key.as_str()) && !is_record_array(value))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// Merge two record lists by id
+/// Records on only one side are reported and kept only when their path is in `keep`
+fn merge_record_arrays(
+    local: &[Value],
+    other: &[Value],
+    path: &str,
+    keep: &HashSet<String>,
+    diffs: &mut Vec<RecordDiff>,
+) -> Value {
+    let mut merged = Vec::with_capacity(local.len());
+    for record in local {
+        let id = record_id(record);
+        let record_path = join_path(path, id);
+        match other.iter().find(|o| record_id(o) == id) {
+            Some(other_record) => merged.push(merge_records(
+                record,
+                other_record,
+                &record_path,
+                keep,
+                diffs,
+            )),
+            None => {
+                if keep.contains(&record_path) {
+                    merged.push(record.clone());
+                }
+                diffs.push(RecordDiff {
+                    path: record_path,
+                    change: RecordChange::OnlyLocal,
+                });
+            }
+        }
+    }
+
+    let local_ids: HashSet<&str> = local.iter().map(record_id).collect();
+    for record in other {
+        if !local_ids.contains(record_id(record)) {
+            let record_path = join_path(path, record_id(record));
+            if keep.contains(&record_path) {
+                merged.push(record.clone());
+            }
+            diffs.push(RecordDiff {
+                path: record_path,
+                change: RecordChange::OnlyConflict,
+            });
+        }
+    }
+    Value::Array(merged)
+}
+
+/// Merge two versions of the same record
+/// Nested record lists are merged by id; when the record's own fields differ the
+/// local version is kept and the record is reported as Changed
+fn merge_records(
+    local: &Value,
+    other: &Value,
+    path: &str,
+    keep: &HashSet<String>,
+    diffs: &mut Vec<RecordDiff>,
+) -> Value {
+    let (Some(local_map), Some(other_map)) = (local.as_object(), other.as_object()) else {
+        if local != other {
+            diffs.push(RecordDiff {
+                path: path.to_string(),
+                change: RecordChange::Changed,
+            });
+        }
+        return local.clone();
+    };
+
+    if own_fields(local_map) != own_fields(other_map) {
+        diffs.push(RecordDiff {
+            path: path.to_string(),
+            change: RecordChange::Changed,
+        });
+    }
+
+    let mut merged = local_map.clone();
+    for (key, other_value) in other_map {
+        let Some(local_value) = local_map.get(key) else {
+            if is_record_array(other_value) {
+                merged.insert(
+                    key.clone(),
+                    merge_record_arrays(
+                        &[],
+                        other_value.as_array().unwrap(),
+                        &join_path(path, key),
+                        keep,
+                        diffs,
+                    ),
+                );
+            }
+            continue;
+        };
+        if is_record_array(local_value) && is_record_array(other_value) {
+            let value = merge_record_arrays(
+                local_value.as_array().unwrap(),
+                other_value.as_array().unwrap(),
+                &join_path(path, key),
+                keep,
+                diffs,
+            );
+            merged.insert(key.clone(), value);
+        }
+    }
+    for (key, local_value) in local_map {
+        if is_record_array(local_value) && !other_map.contains_key(key) {
+            let value = merge_record_arrays(
+                local_value.as_array().unwrap(),
+                &[],
+                &join_path(path, key),
+                keep,
+                diffs,
+            );
+            merged.insert(key.clone(), value);
+        }
+    }
+
+    // Keep the newer modification time
+    for field in IGNORED_FIELDS {
+        if let (Some(Value::String(a)), Some(Value::String(b))) =
+            (local_map.get(field), other_map.get(field))
+        {
+            if b > a {
+                merged.insert(field.to_string(), Value::String(b.clone()));
+            }
+        }
+    }
+    Value::Object(merged)
+}
+
+/// Diff and merge both versions of a data file
+fn merge_documents(
+    local: &Value,
+    other: &Value,
+    keep: &HashSet<String>,
+) -> (Value, Vec<RecordDiff>) {
+    let mut diffs = Vec::new();
+    let merged = match (local.as_array(), other.as_array()) {
+        (Some(local), Some(other)) if is_records(local) && is_records(other) => {
+            merge_record_arrays(local, other, "", keep, &mut diffs)
+        }
+        _ => merge_records(local, other, "", keep, &mut diffs),
+    };
+    (merged, diffs)
+}
+
+fn read_json(path: &Path) -> Result<Value, String> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let content = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+    serde_json::from_slice(&content).map_err(|e| format!("Invalid JSON in {}: {}", name, e))
+}
+
+/// Read both versions and merge them
+/// A missing original merges like an empty file of the same type
+fn merge_files(
+    local_path: &Path,
+    conflict_path: &Path,
+    keep: &HashSet<String>,
+) -> Result<(Value, Vec<RecordDiff>), String> {
+    let other = read_json(conflict_path)?;
+    let local = if local_path.exists() {
+        read_json(local_path)?
+    } else if other.is_array() {
+        Value::Array(Vec::new())
+    } else {
+        Value::Object(Map::new())
+    };
+    Ok(merge_documents(&local, &other, keep))
+}
+
+/// Build the conflict report for one conflict copy
+fn describe(
+    data_dir: &Path,
+    dir: &str,
+    name: &str,
+    original: &str,
+    kind: ConflictKind,
+) -> SyncConflict {
+    let local_path = data_dir.join(dir).join(original);
+    let mut conflict = SyncConflict {
+        conflict_file: format!("{}/{}", dir, name),
+        file: format!("{}/{}", dir, original),
+        kind,
+        local_exists: local_path.exists(),
+        error: None,
+        differences: Vec::new(),
+        can_merge: false,
+    };
+    if kind == ConflictKind::NotDownloaded {
+        return conflict;
+    }
+
+    match merge_files(&local_path, &data_dir.join(dir).join(name), &HashSet::new()) {
+        Ok((_, differences)) => {
+            conflict.can_merge = !differences
+                .iter()
+                .any(|d| d.change == RecordChange::Changed);
+            conflict.differences = differences;
+        }
+        Err(e) => conflict.error = Some(e),
+    }
+    conflict
+}
+
+/// Find every conflict copy in `entities/` and `months/`
+pub(crate) fn find_conflicts(data_dir: &Path) -> Vec<SyncConflict> {
+    let mut conflicts = Vec::new();
+    for dir in CONFLICT_DIRS {
+        let Ok(entries) = std::fs::read_dir(data_dir.join(dir)) else {
+            continue;
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_file())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        let existing: HashSet<String> = names.iter().cloned().collect();
+
+        for name in &names {
+            if let Some((original, kind)) = conflict_original(name, &existing) {
+                conflicts.push(describe(data_dir, dir, name, &original, kind));
+            }
+        }
+    }
+    conflicts
+}
+
+/// Write JSON the way the backend does (2-space indent), through a temp file
+/// The temp name does not follow the backend's `.tmp-<pid>` pattern, so the data
+/// watcher reports the change and the frontend reloads the file
+fn write_json(path: &Path, value: &Value) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize merged data: {}", e))?;
+    let temp_path = PathBuf::from(format!("{}.resolving", path.to_string_lossy()));
+    std::fs::write(&temp_path, content)
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    std::fs::rename(&temp_path, path).map_err(|e| {
+        let _ = std::fs::remove_file(&temp_path);
+        format!("Failed to replace {:?}: {}", path, e)
+    })
+}
+
+fn resolve(
+    data_dir: &Path,
+    conflict_file: &str,
+    resolution: SyncResolution,
+    keep_records: Option<Vec<String>>,
+) -> Result<String, String> {
+    let (dir, name) = conflict_file
+        .split_once('/')
+        .filter(|(dir, name)| {
+            CONFLICT_DIRS.contains(dir) && !name.contains('/') && !name.contains('\\')
+        })
+        .ok_or_else(|| format!("Not a data file: {}", conflict_file))?;
+
+    let conflict_path = data_dir.join(dir).join(name);
+    if !conflict_path.is_file() {
+        return Err(format!("Conflict copy not found: {}", conflict_file));
+    }
+    let existing: HashSet<String> = std::fs::read_dir(data_dir.join(dir))
+        .map_err(|e| format!("Failed to read {}: {}", dir, e))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    let (original, kind) = conflict_original(name, &existing)
+        .ok_or_else(|| format!("Not a sync conflict copy: {}", conflict_file))?;
+    if kind == ConflictKind::NotDownloaded {
+        return Err(format!(
+            "{}/{} has not been downloaded by iCloud yet; open the folder in Finder to download it",
+            dir, original
+        ));
+    }
+    let local_path = data_dir.join(dir).join(&original);
+
+    match resolution {
+        SyncResolution::KeepLocal => {}
+        SyncResolution::KeepConflict => {
+            let other = read_json(&conflict_path)?;
+            write_json(&local_path, &other)?;
+        }
+        SyncResolution::Merge => {
+            let conflict = describe(data_dir, dir, name, &original, kind);
+            if let Some(error) = conflict.error {
+                return Err(error);
+            }
+            if !conflict.can_merge {
+                let changed: Vec<String> = conflict
+                    .differences
+                    .iter()
+                    .filter(|d| d.change == RecordChange::Changed)
+                    .map(|d| {
+                        if d.path.is_empty() {
+                            "(month fields)".to_string()
+                        } else {
+                            d.path.clone()
+                        }
+                    })
+                    .collect();
+                return Err(format!(
+                    "Records changed on both devices: {}",
+                    changed.join(", ")
+                ));
+            }
+            // A one-sided record is new on one device or deleted on the other;
+            // only the user can tell which
+            let keep: HashSet<String> = match keep_records {
+                Some(keep) => keep.into_iter().collect(),
+                None if conflict.differences.is_empty() => HashSet::new(),
+                None => {
+                    let one_sided: Vec<&str> = conflict
+                        .differences
+                        .iter()
+                        .map(|d| d.path.as_str())
+                        .collect();
+                    return Err(format!(
+                        "Choose which records on only one device to keep: {}",
+                        one_sided.join(", ")
+                    ));
+                }
+            };
+            let (merged, _) = merge_files(&local_path, &conflict_path, &keep)?;
+            write_json(&local_path, &merged)?;
+        }
+    }
+
+    std::fs::remove_file(&conflict_path)
+        .map_err(|e| format!("Failed to remove conflict copy: {}", e))?;
+    info!("[Sync] Resolved {} ({:?})", conflict_file, resolution);
+    Ok(format!("{}/{}", dir, original))
+}
+
+/// List the cloud-sync conflict copies in the data directory
+/// Defaults to the data directory of the running sidecar
+#[tauri::command]
+pub(crate) async fn list_sync_conflicts(
+    app: tauri::AppHandle,
+    data_dir: Option<String>,
+) -> Result<Vec<SyncConflict>, String> {
+    let data_dir = match data_dir {
+        Some(dir) => crate::resolve_data_dir(Some(dir))?,
+        None => snapshots::current_data_dir(&app)?,
+    };
+    tauri::async_runtime::spawn_blocking(move || find_conflicts(Path::new(&data_dir)))
+        .await
+        .map_err(|e| format!("Failed to list sync conflicts: {}", e))
+}
+
+/// Resolve a conflict copy by keeping one side or merging both
+/// A merge keeps the records on only one side whose paths are in `keep_records`,
+/// and is refused without it when there are any.
+/// Takes a snapshot first so the discarded version can be recovered.
+/// Returns the resolved file, relative to the data directory.
+#[tauri::command]
+pub(crate) async fn resolve_sync_conflict(
+    app: tauri::AppHandle,
+    conflict_file: String,
+    resolution: SyncResolution,
+    keep_records: Option<Vec<String>>,
+) -> Result<String, String> {
+    let data_dir = snapshots::current_data_dir(&app)?;
+    snapshots::take_snapshot(&app, data_dir.clone(), SnapshotReason::PreResolve).await?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        resolve(
+            Path::new(&data_dir),
+            &conflict_file,
+            resolution,
+            keep_records,
+        )
+    })
+    .await
+    .map_err(|e| format!("Failed to resolve sync conflict: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn keep(paths: &[&str]) -> HashSet<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    fn changes(diffs: &[RecordDiff]) -> Vec<(&str, RecordChange)> {
+        diffs.iter().map(|d| (d.path.as_str(), d.change)).collect()
+    }
+
+    #[test]
+    fn matching_records_have_no_differences() {
+        let local = json!([{ "id": "a", "name": "Rent", "updated_at": "2026-01-01" }]);
+        let other = json!([{ "id": "a", "name": "Rent", "updated_at": "2026-01-02" }]);
+        let (merged, diffs) = merge_documents(&local, &other, &HashSet::new());
+        assert!(diffs.is_empty());
+        // The newer modification time wins
+        assert_eq!(
+            merged,
+            json!([{ "id": "a", "name": "Rent", "updated_at": "2026-01-02" }])
+        );
+    }
+
+    #[test]
+    fn changed_own_fields_keep_the_local_record() {
+        let local = json!([{ "id": "a", "amount": 100 }]);
+        let other = json!([{ "id": "a", "amount": 120 }]);
+        let (merged, diffs) = merge_documents(&local, &other, &HashSet::new());
+        assert_eq!(changes(&diffs), vec![("a", RecordChange::Changed)]);
+        assert_eq!(merged, local);
+    }
+
+    #[test]
+    fn nested_records_are_diffed_by_path() {
+        let local = json!({
+            "month": "2026-01",
+            "bill_instances": [{
+                "id": "bi1",
+                "occurrences": [{ "id": "o1", "payments": [{ "id": "p1", "amount": 50 }] }]
+            }]
+        });
+        let other = json!({
+            "month": "2026-01",
+            "bill_instances": [{
+                "id": "bi1",
+                "occurrences": [{
+                    "id": "o1",
+                    "payments": [{ "id": "p1", "amount": 50 }, { "id": "p2", "amount": 25 }]
+                }]
+            }]
+        });
+        let (_, diffs) = merge_documents(&local, &other, &HashSet::new());
+        assert_eq!(
+            changes(&diffs),
+            vec![(
+                "bill_instances/bi1/occurrences/o1/payments/p2",
+                RecordChange::OnlyConflict
+            )]
+        );
+
+        // A payment added to a record is not a change of the record's own fields
+        let (merged, _) = merge_documents(
+            &local,
+            &other,
+            &keep(&["bill_instances/bi1/occurrences/o1/payments/p2"]),
+        );
+        assert_eq!(merged, other);
+    }
+
+    #[test]
+    fn one_sided_records_are_only_kept_when_chosen() {
+        let local = json!([{ "id": "a" }, { "id": "b" }]);
+        let other = json!([{ "id": "a" }, { "id": "c" }]);
+
+        let (merged, diffs) = merge_documents(&local, &other, &HashSet::new());
+        assert_eq!(
+            changes(&diffs),
+            vec![
+                ("b", RecordChange::OnlyLocal),
+                ("c", RecordChange::OnlyConflict)
+            ]
+        );
+        assert_eq!(merged, json!([{ "id": "a" }]));
+
+        let (merged, _) = merge_documents(&local, &other, &keep(&["b", "c"]));
+        assert_eq!(merged, json!([{ "id": "a" }, { "id": "b" }, { "id": "c" }]));
+    }
+
+    #[test]
+    fn record_list_missing_on_one_side() {
+        let local = json!({ "id": "bi1", "occurrences": [{ "id": "o1" }] });
+        let other = json!({ "id": "bi1" });
+        let (merged, diffs) = merge_documents(&local, &other, &HashSet::new());
+        assert_eq!(
+            changes(&diffs),
+            vec![("occurrences/o1", RecordChange::OnlyLocal)]
+        );
+        assert_eq!(merged, json!({ "id": "bi1", "occurrences": [] }));
+    }
+
+    #[test]
+    fn conflict_copy_names() {
+        let existing = keep(&["bills.json", "2026-01.json"]);
+        let original = |name: &str| conflict_original(name, &existing).map(|(o, _)| o);
+        assert_eq!(
+            original("2026-01 (conflicted copy).json").as_deref(),
+            Some("2026-01.json")
+        );
+        assert_eq!(
+            original("bills.sync-conflict-20260102-101500-ABCDEFG.json").as_deref(),
+            Some("bills.json")
+        );
+        assert_eq!(original("bills (1).json").as_deref(), Some("bills.json"));
+        assert_eq!(original("bills 2.json").as_deref(), Some("bills.json"));
+        assert_eq!(original("incomes 2.json"), None);
+        assert_eq!(
+            conflict_original(".bills.json.icloud", &existing),
+            Some(("bills.json".to_string(), ConflictKind::NotDownloaded))
+        );
+    }
+}
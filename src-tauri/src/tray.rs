@@ -0,0 +1,310 @@
+// System tray icon with the current month's leftover.
+//
+// The tray menu shows the leftover and the number of overdue bills for the
+// current month, taken from the sidecar's detailed month view (the same
+// numbers the month page shows), plus shortcuts into the app. The summary is
+// refreshed every minute, when the sidecar becomes ready and when the data
+// changes on another device.
+//
+// While the tray exists, closing the main window only hides it: the sidecar
+// keeps running and Quit in the tray menu exits the app. Without a tray (e.g.
+// a Linux desktop without an appindicator host) closing works as before.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{Listener, Manager};
+use tokio::sync::Notify;
+use tracing::{error, info};
+
+use crate::api_socket::Endpoint;
+use crate::deep_link::{navigate, NavigateTarget};
+use crate::sidecar_status::SidecarPhase;
+use crate::windows::MAIN_WINDOW;
+use crate::SidecarState;
+
+/// Id of the tray icon
+const TRAY_ID: &str = "main";
+
+/// Time between summary refreshes
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A summary request that takes longer than this is abandoned
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Menu items updated with the summary
+pub(crate) struct TrayState {
+    leftover: MenuItem<tauri::Wry>,
+    overdue: MenuItem<tauri::Wry>,
+    /// Due items with their actions (see reminders.rs)
+    reminders: Submenu<tauri::Wry>,
+}
+
+/// Leftover and overdue bills of the current month
+struct MonthSummary {
+    /// Leftover in cents, None when bank balances are missing
+    leftover: Option<i64>,
+    overdue_bills: usize,
+}
+
+/// Create the tray icon and start refreshing its summary
+pub(crate) fn setup(app: &tauri::AppHandle) -> tauri::Result<()> {
+    let leftover = MenuItem::with_id(app, "leftover", "Leftover: -", false, None::<&str>)?;
+    let overdue = MenuItem::with_id(app, "overdue", "Overdue bills: -", false, None::<&str>)?;
+    let reminders = Submenu::with_id(app, "reminders", "Reminders", false)?;
+    let open = MenuItem::with_id(app, "open", "Open Doggy Bag", true, None::<&str>)?;
+    let quick_add = MenuItem::with_id(app, "quick-add", "Quick Add Expense", true, None::<&str>)?;
+    let calendar = MenuItem::with_id(app, "calendar", "Open Calendar", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &leftover,
+            &overdue,
+            &reminders,
+            &PredefinedMenuItem::separator(app)?,
+            &open,
+            &quick_add,
+            &calendar,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )?;
+
+    let mut tray_builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Doggy Bag")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            "open" => show_main_window(app),
+            "quick-add" => navigate(
+                app,
+                NavigateTarget::QuickAdd {
+                    month: current_month(),
+                },
+            ),
+            "calendar" => navigate(app, NavigateTarget::Calendar),
+            "quit" => {
+                info!("[Tray] Quit requested");
+                app.exit(0);
+            }
+            id if id.starts_with(crate::reminders::MENU_PREFIX) => {
+                crate::reminders::handle_menu_action(app, id)
+            }
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray_builder = tray_builder.icon(icon.clone());
+    }
+    tray_builder.build(app)?;
+
+    app.manage(TrayState {
+        leftover,
+        overdue,
+        reminders,
+    });
+
+    let refresh = Arc::new(Notify::new());
+    for event in [
+        "sidecar-status-changed",
+        "data-changed-externally",
+        "reminder-completed",
+    ] {
+        let refresh = refresh.clone();
+        app.listen(event, move |_| refresh.notify_one());
+    }
+    spawn_refresher(app.clone(), refresh);
+    Ok(())
+}
+
+/// Keep the main window alive in the tray instead of closing it
+pub(crate) fn handle_window_event(window: &tauri::Window, event: &tauri::WindowEvent) {
+    if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+        if window.label() == MAIN_WINDOW && window.try_state::<TrayState>().is_some() {
+            api.prevent_close();
+            let _ = window.hide();
+        }
+    }
+}
+
+/// Bring back the main window (hidden by closing it)
+pub(crate) fn show_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Replace the Reminders submenu with `items` (key, label, whether it is a bill)
+pub(crate) fn set_reminders(app: &tauri::AppHandle, items: &[(String, String, bool)]) {
+    let Some(state) = app.try_state::<TrayState>() else {
+        return;
+    };
+    let result = (|| -> tauri::Result<()> {
+        for item in state.reminders.items()? {
+            state.reminders.remove(&item)?;
+        }
+        for (key, label, is_bill) in items {
+            let complete_label = if *is_bill { "Mark Paid" } else { "Mark Done" };
+            let entry = Submenu::with_items(
+                app,
+                label,
+                true,
+                &[
+                    &MenuItem::with_id(
+                        app,
+                        format!("{}complete:{}", crate::reminders::MENU_PREFIX, key),
+                        complete_label,
+                        true,
+                        None::<&str>,
+                    )?,
+                    &MenuItem::with_id(
+                        app,
+                        format!("{}snooze:{}", crate::reminders::MENU_PREFIX, key),
+                        "Snooze 1 Day",
+                        true,
+                        None::<&str>,
+                    )?,
+                ],
+            )?;
+            state.reminders.append(&entry)?;
+        }
+        state.reminders.set_enabled(!items.is_empty())?;
+        state
+            .reminders
+            .set_text(format!("Reminders ({})", items.len()))
+    })();
+    if let Err(e) = result {
+        error!("[Tray] Failed to update reminders: {}", e);
+    }
+}
+
+/// Month shown by the tray, e.g. "2026-01"
+fn current_month() -> String {
+    chrono::Local::now().format("%Y-%m").to_string()
+}
+
+/// Format cents as dollars with thousands separators, e.g. -123456 -> "-$1,234.56"
+pub(crate) fn format_cents(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let cents = cents.unsigned_abs();
+    let dollars = (cents / 100).to_string();
+    let mut grouped = String::new();
+    for (i, digit) in dollars.chars().enumerate() {
+        if i > 0 && (dollars.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    format!("{}${}.{:02}", sign, grouped, cents % 100)
+}
+
+async fn fetch_summary(
+    client: &reqwest::Client,
+    endpoint: &Endpoint,
+) -> Result<MonthSummary, String> {
+    let url = endpoint.url(&format!("/api/months/{}/detailed", current_month()));
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err("No data for this month yet".to_string());
+    }
+    if !response.status().is_success() {
+        return Err(format!("Backend returned {}", response.status()));
+    }
+    let json: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid response: {}", e))?;
+
+    let is_valid = json
+        .pointer("/leftoverBreakdown/isValid")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    Ok(MonthSummary {
+        leftover: json
+            .get("leftover")
+            .and_then(|v| v.as_f64())
+            .filter(|_| is_valid)
+            .map(|v| v.round() as i64),
+        overdue_bills: json
+            .get("overdue_bills")
+            .and_then(|v| v.as_array())
+            .map_or(0, |bills| bills.len()),
+    })
+}
+
+/// Update the menu items and tooltip
+fn show_summary(app: &tauri::AppHandle, summary: Result<MonthSummary, String>) {
+    let state = app.state::<TrayState>();
+    let (leftover, overdue, tooltip) = match summary {
+        Ok(summary) => {
+            let leftover = match summary.leftover {
+                Some(cents) => format!("Leftover: {}", format_cents(cents)),
+                None => "Leftover: enter bank balances".to_string(),
+            };
+            let overdue = format!("Overdue bills: {}", summary.overdue_bills);
+            let tooltip = format!("Doggy Bag\n{}\n{}", leftover, overdue);
+            (leftover, overdue, tooltip)
+        }
+        Err(reason) => (
+            format!("Leftover: {}", reason),
+            "Overdue bills: -".to_string(),
+            "Doggy Bag".to_string(),
+        ),
+    };
+    let _ = state.leftover.set_text(leftover);
+    let _ = state.overdue.set_text(overdue);
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(tooltip));
+    }
+}
+
+fn spawn_refresher(app: tauri::AppHandle, refresh: Arc<Notify>) {
+    tauri::async_runtime::spawn(async move {
+        let client = match crate::api_token::client(&app, REQUEST_TIMEOUT) {
+            Ok(client) => client,
+            Err(e) => {
+                error!("[Tray] {}", e);
+                return;
+            }
+        };
+
+        loop {
+            let endpoint = {
+                let state = app.state::<Mutex<SidecarState>>();
+                let state_guard = state.lock().unwrap();
+                match state_guard.phase {
+                    SidecarPhase::Ready | SidecarPhase::Degraded => state_guard.endpoint.clone(),
+                    _ => None,
+                }
+            };
+            let summary = match &endpoint {
+                Some(endpoint) => fetch_summary(&client, endpoint).await,
+                None => Err("backend not running".to_string()),
+            };
+            show_summary(&app, summary);
+
+            tokio::select! {
+                _ = tokio::time::sleep(REFRESH_INTERVAL) => {}
+                _ = refresh.notified() => {}
+            }
+        }
+    });
+}
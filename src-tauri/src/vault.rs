@@ -0,0 +1,1236 @@
+// Encrypted-at-rest data directory (opt-in vault mode).
+//
+// With the vault enabled, every data file under entities/, months/,
+// data/documents/ and backups/, plus version.json, is stored encrypted as
+// `<file>.enc` next to a `vault.json` header in DATA_DIR, so nothing readable
+// ends up on a cloud drive.
+//
+// Keys: a random 256-bit data key encrypts the files (XChaCha20-Poly1305,
+// with the file's relative path as associated data so files cannot be
+// swapped). The data key is stored in the header wrapped with a key derived
+// from the passphrase with Argon2id, so changing the passphrase only rewraps
+// the data key.
+//
+// The sidecar is unchanged: after unlocking, the host decrypts the vault into
+// a private working directory (tmpfs under XDG_RUNTIME_DIR on Linux, readable
+// only by the user) and starts the sidecar on it. macOS and Windows have no
+// runtime directory, so there the working copy lives in the app's local data
+// directory on disk and relies on full-disk encryption (FileVault, BitLocker)
+// while the vault is unlocked. Changes are
+// encrypted back into the vault every few seconds and whenever the sidecar
+// stops; locking the vault or quitting wipes the working directory. A file
+// only counts as deleted when the directory it was in could still be read, so
+// a working directory wiped from under the app (logout clears
+// XDG_RUNTIME_DIR) never empties the vault.
+// Snapshots, the data directory lock and the file watcher keep working on
+// DATA_DIR itself and so only ever see encrypted files; files another device
+// changed are decrypted into the working copy (see data_watcher.rs) before the
+// next flush could write the stale copy back.
+//
+// The passphrase can be remembered in the platform keychain (see secrets.rs);
+// a remembered passphrase unlocks the vault when the sidecar starts.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::Manager;
+use tracing::{error, info};
+use zeroize::Zeroizing;
+
+use crate::sidecar_status::{self, now_millis, SidecarPhase};
+use crate::snapshots::SNAPSHOT_DIRS;
+
+/// Vault header file in the data directory
+pub(crate) const HEADER_FILE: &str = "vault.json";
+
+/// Suffix of encrypted data files
+pub(crate) const ENCRYPTED_SUFFIX: &str = ".enc";
+
+/// Magic bytes at the start of every encrypted file (format version 1)
+const FILE_MAGIC: &[u8; 4] = b"DBV1";
+
+/// File in the working directory naming the vault it was decrypted from
+const SOURCE_MARKER: &str = ".vault-source";
+
+/// Directories the backend writes besides SNAPSHOT_DIRS (version backups)
+const EXTRA_DIRS: [&str; 1] = ["backups"];
+
+/// Files the backend writes in the data directory itself
+const ROOT_FILES: [&str; 1] = ["version.json"];
+
+/// How often changes in the working directory are encrypted back into the vault
+const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Argon2id cost for new vaults (stored in the header, so it can be raised later)
+const KDF_MEMORY_KIB: u32 = 64 * 1024;
+const KDF_ITERATIONS: u32 = 3;
+const KDF_PARALLELISM: u32 = 1;
+
+type DataKey = Zeroizing<[u8; 32]>;
+
+/// Argon2id parameters stored in the vault header
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KdfParams {
+    algorithm: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    salt: String,
+}
+
+/// Contents of `vault.json`
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultHeader {
+    version: u32,
+    created_at: u64,
+    kdf: KdfParams,
+    /// Data key encrypted with the passphrase key (nonce || ciphertext, base64)
+    wrapped_key: String,
+}
+
+/// Unlocked vault, managed as `Mutex<VaultState>`
+#[derive(Default)]
+pub(crate) struct VaultState {
+    /// Data directory the key belongs to
+    data_dir: Option<PathBuf>,
+    key: Option<DataKey>,
+    /// Decrypted working directory the sidecar runs on
+    work_dir: Option<PathBuf>,
+    /// Size and modification time of each working file when it was last encrypted
+    synced: HashMap<String, (u64, Option<SystemTime>)>,
+}
+
+pub(crate) enum VaultError {
+    /// The data directory is a vault and no key is loaded
+    Locked,
+    Io(String),
+}
+
+/// Vault state returned by `get_vault_status`
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct VaultStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+    pub data_dir: String,
+}
+
+/// Keychain entry holding the remembered passphrase for `data_dir`
+fn passphrase_secret_key(data_dir: &Path) -> String {
+    format!("vault:{}", data_dir.to_string_lossy())
+}
+
+fn header_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(HEADER_FILE)
+}
+
+/// Whether `data_dir` holds an encrypted vault
+pub(crate) fn is_vault(data_dir: &Path) -> bool {
+    header_path(data_dir).is_file()
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn derive_key(passphrase: &str, kdf: &KdfParams) -> Result<DataKey, String> {
+    if kdf.algorithm != "argon2id" {
+        return Err(format!("Unsupported key derivation: {}", kdf.algorithm));
+    }
+    let salt = BASE64
+        .decode(&kdf.salt)
+        .map_err(|e| format!("Invalid vault salt: {}", e))?;
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(32))
+        .map_err(|e| format!("Invalid vault parameters: {}", e))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &salt, key.as_mut())
+        .map_err(|e| format!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+fn new_kdf_params() -> KdfParams {
+    KdfParams {
+        algorithm: "argon2id".to_string(),
+        memory_kib: KDF_MEMORY_KIB,
+        iterations: KDF_ITERATIONS,
+        parallelism: KDF_PARALLELISM,
+        salt: BASE64.encode(random_bytes::<16>()),
+    }
+}
+
+/// Encrypt `data` as nonce || ciphertext
+fn seal(key: &[u8; 32], data: &[u8], aad: &[u8]) -> Result<Vec<u8>, String> {
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, Payload { msg: data, aad })
+        .map_err(|_| "Encryption failed".to_string())?;
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Decrypt nonce || ciphertext; fails if the data or its associated data were changed
+fn open(key: &[u8; 32], sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>, String> {
+    if sealed.len() < 24 {
+        return Err("Encrypted data is truncated".to_string());
+    }
+    let (nonce, ciphertext) = sealed.split_at(24);
+    XChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| "Decryption failed".to_string())
+}
+
+fn read_header(data_dir: &Path) -> Result<VaultHeader, String> {
+    let content = std::fs::read(header_path(data_dir))
+        .map_err(|e| format!("Failed to read vault header: {}", e))?;
+    serde_json::from_slice(&content).map_err(|e| format!("Invalid vault header: {}", e))
+}
+
+/// Write a file through a temp file and rename it into place
+fn write_atomic(path: &Path, content: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+    }
+    let temp_path = PathBuf::from(format!("{}.vault-tmp", path.to_string_lossy()));
+    std::fs::write(&temp_path, content)
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    std::fs::rename(&temp_path, path).map_err(|e| {
+        let _ = std::fs::remove_file(&temp_path);
+        format!("Failed to write {:?}: {}", path, e)
+    })
+}
+
+fn write_header(data_dir: &Path, header: &VaultHeader) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(header)
+        .map_err(|e| format!("Failed to serialize vault header: {}", e))?;
+    write_atomic(&header_path(data_dir), &content)
+}
+
+/// Wrap `data_key` with a new passphrase (fresh salt)
+fn wrap_key(data_key: &[u8; 32], passphrase: &str) -> Result<(KdfParams, String), String> {
+    let kdf = new_kdf_params();
+    let passphrase_key = derive_key(passphrase, &kdf)?;
+    let wrapped = seal(&passphrase_key, data_key, b"doggybag-vault-key")?;
+    Ok((kdf, BASE64.encode(wrapped)))
+}
+
+/// Unwrap the data key; a wrong passphrase fails authentication
+fn unwrap_key(header: &VaultHeader, passphrase: &str) -> Result<DataKey, String> {
+    let passphrase_key = derive_key(passphrase, &header.kdf)?;
+    let wrapped = BASE64
+        .decode(&header.wrapped_key)
+        .map_err(|e| format!("Invalid vault header: {}", e))?;
+    let data_key = open(&passphrase_key, &wrapped, b"doggybag-vault-key")
+        .map_err(|_| "Incorrect passphrase".to_string())?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    if data_key.len() != key.len() {
+        return Err("Invalid vault key".to_string());
+    }
+    key.copy_from_slice(&data_key);
+    Ok(key)
+}
+
+fn encrypt_file(key: &[u8; 32], rel: &str, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let mut content = FILE_MAGIC.to_vec();
+    content.extend(seal(key, plaintext, rel.as_bytes())?);
+    Ok(content)
+}
+
+fn decrypt_file(key: &[u8; 32], rel: &str, content: &[u8]) -> Result<Vec<u8>, String> {
+    let sealed = content
+        .strip_prefix(FILE_MAGIC.as_slice())
+        .ok_or_else(|| format!("{} is not a vault file", rel))?;
+    open(key, sealed, rel.as_bytes()).map_err(|e| format!("{}: {}", rel, e))
+}
+
+/// Data files found in a vault or working directory
+#[derive(Default)]
+struct DataFiles {
+    /// (absolute path, relative path)
+    files: Vec<(PathBuf, String)>,
+    /// Relative directories that were listed completely ("" is the directory itself)
+    read_dirs: HashSet<String>,
+}
+
+impl DataFiles {
+    /// Whether `rel` is known to be gone: its directory was listed without it
+    fn is_deleted(&self, rel: &str) -> bool {
+        let parent = rel.rsplit_once('/').map_or("", |(parent, _)| parent);
+        self.read_dirs.contains(parent) && !self.files.iter().any(|(_, file)| file == rel)
+    }
+}
+
+/// Every file under the vault directories and the root files of `dir`
+/// (in-flight temp files excluded)
+fn data_files(dir: &Path) -> DataFiles {
+    fn walk(dir: &Path, rel: &str, out: &mut DataFiles) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        let mut complete = true;
+        for entry in entries {
+            let Ok(entry) = entry else {
+                complete = false;
+                continue;
+            };
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let rel_path = format!("{}/{}", rel, name);
+            if path.is_dir() {
+                walk(&path, &rel_path, out);
+            } else if path.is_file() && !name.contains(".tmp-") && !name.ends_with(".vault-tmp") {
+                out.files.push((path, rel_path));
+            }
+        }
+        if complete {
+            out.read_dirs.insert(rel.to_string());
+        }
+    }
+
+    let mut out = DataFiles::default();
+    for subdir in SNAPSHOT_DIRS.iter().chain(EXTRA_DIRS.iter()) {
+        walk(&dir.join(subdir), subdir, &mut out);
+    }
+    if std::fs::read_dir(dir).is_ok() {
+        out.read_dirs.insert(String::new());
+        for name in ROOT_FILES {
+            for rel in [name.to_string(), format!("{}{}", name, ENCRYPTED_SUFFIX)] {
+                let path = dir.join(&rel);
+                if path.is_file() {
+                    out.files.push((path, rel));
+                }
+            }
+        }
+    }
+    out.files.sort_by(|a, b| a.1.cmp(&b.1));
+    out
+}
+
+/// Encrypted files in the vault as (absolute path, relative plaintext path)
+fn encrypted_files(data_dir: &Path) -> Vec<(PathBuf, String)> {
+    data_files(data_dir)
+        .files
+        .into_iter()
+        .filter_map(|(path, rel)| {
+            let plain = rel.strip_suffix(ENCRYPTED_SUFFIX)?.to_string();
+            Some((path, plain))
+        })
+        .collect()
+}
+
+/// Every data file in `dir`, plain or encrypted, as (absolute path, relative path)
+/// data_migration.rs copies these, so plain and encrypted directories move the same set.
+pub(crate) fn data_dir_files(dir: &Path) -> Vec<(PathBuf, String)> {
+    data_files(dir).files
+}
+
+/// Plaintext files in a directory (everything that is not an encrypted file)
+fn plaintext_files(dir: &Path) -> DataFiles {
+    let mut found = data_files(dir);
+    found
+        .files
+        .retain(|(_, rel)| !rel.ends_with(ENCRYPTED_SUFFIX));
+    found
+}
+
+fn file_stamp(path: &Path) -> (u64, Option<SystemTime>) {
+    std::fs::metadata(path)
+        .map(|m| (m.len(), m.modified().ok()))
+        .unwrap_or((0, None))
+}
+
+/// Private directory for the decrypted working copy
+fn work_dir_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    // Prefer a tmpfs (XDG_RUNTIME_DIR on Linux) so plaintext never reaches the disk;
+    // elsewhere the copy is on disk, readable only by the user, until it is wiped
+    let base = match dirs::runtime_dir() {
+        Some(dir) => dir.join(app.config().identifier.as_str()),
+        None => app
+            .path()
+            .app_local_data_dir()
+            .map_err(|e| format!("Could not get local data dir: {}", e))?,
+    };
+    Ok(base.join("vault-session"))
+}
+
+fn create_private_dir(path: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(path)
+        .map_err(|e| format!("Failed to create vault working directory: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o700))
+            .map_err(|e| format!("Failed to protect vault working directory: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Whether `work_dir` holds the decrypted copy of `data_dir` (see SOURCE_MARKER)
+fn decrypted_from(work_dir: &Path, data_dir: &Path) -> bool {
+    std::fs::read_to_string(work_dir.join(SOURCE_MARKER))
+        .is_ok_and(|source| Path::new(&source) == data_dir)
+}
+
+/// Save changes left in the working directory by a session that ended without
+/// closing the vault (crash or forced quit): files newer than their encrypted
+/// copy are encrypted before the working directory is replaced
+fn recover_stale_work_dir(key: &[u8; 32], data_dir: &Path, work_dir: &Path) {
+    if !decrypted_from(work_dir, data_dir) {
+        return;
+    }
+
+    for (path, rel) in plaintext_files(work_dir).files {
+        let encrypted_path = data_dir.join(format!("{}{}", rel, ENCRYPTED_SUFFIX));
+        let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+        if modified(&encrypted_path) >= modified(&path) {
+            continue;
+        }
+        let result = std::fs::read(&path)
+            .map_err(|e| format!("Failed to read {}: {}", rel, e))
+            .and_then(|plaintext| encrypt_file(key, &rel, &Zeroizing::new(plaintext)))
+            .and_then(|encrypted| write_atomic(&encrypted_path, &encrypted));
+        match result {
+            Ok(()) => info!("[Vault] Recovered unsaved changes to {}", rel),
+            Err(e) => error!("[Vault] Failed to recover {}: {}", rel, e),
+        }
+    }
+}
+
+/// Decrypt the whole vault into a fresh working directory
+fn open_working_dir(
+    state: &mut VaultState,
+    data_dir: &Path,
+    work_dir: PathBuf,
+) -> Result<PathBuf, String> {
+    let key = state.key.as_ref().ok_or("Vault is locked")?;
+
+    recover_stale_work_dir(key, data_dir, &work_dir);
+    let _ = std::fs::remove_dir_all(&work_dir);
+    create_private_dir(&work_dir)?;
+    std::fs::write(
+        work_dir.join(SOURCE_MARKER),
+        data_dir.to_string_lossy().as_bytes(),
+    )
+    .map_err(|e| format!("Failed to prepare vault working directory: {}", e))?;
+
+    let mut synced = HashMap::new();
+    for (path, rel) in encrypted_files(data_dir) {
+        let content = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+        let plaintext = Zeroizing::new(decrypt_file(key, &rel, &content)?);
+        let target = work_dir.join(&rel);
+        write_atomic(&target, &plaintext)?;
+        synced.insert(rel, file_stamp(&target));
+    }
+
+    info!(
+        "[Vault] Decrypted {} files into the working directory",
+        synced.len()
+    );
+    state.synced = synced;
+    state.work_dir = Some(work_dir.clone());
+    Ok(work_dir)
+}
+
+/// Encrypt changed working files back into the vault and drop deleted ones
+/// Fails without touching the vault when the working directory is gone or was
+/// decrypted from another vault.
+fn flush_state(state: &mut VaultState) -> Result<usize, String> {
+    let (Some(key), Some(work_dir), Some(data_dir)) = (
+        state.key.as_ref(),
+        state.work_dir.clone(),
+        state.data_dir.clone(),
+    ) else {
+        return Ok(0);
+    };
+    if !decrypted_from(&work_dir, &data_dir) {
+        return Err(format!(
+            "Working directory {} is missing or belongs to another vault, keeping the vault as it is",
+            work_dir.display()
+        ));
+    }
+
+    let found = plaintext_files(&work_dir);
+    let mut written = 0;
+    let mut seen = HashMap::new();
+    for (path, rel) in &found.files {
+        let stamp = file_stamp(path);
+        seen.insert(rel.clone(), stamp);
+        if state.synced.get(rel) == Some(&stamp) {
+            continue;
+        }
+        let plaintext = Zeroizing::new(
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", rel, e))?,
+        );
+        let encrypted = encrypt_file(key, rel, &plaintext)?;
+        write_atomic(
+            &data_dir.join(format!("{}{}", rel, ENCRYPTED_SUFFIX)),
+            &encrypted,
+        )?;
+        written += 1;
+    }
+
+    for (rel, stamp) in &state.synced {
+        if seen.contains_key(rel) {
+            continue;
+        }
+        if found.is_deleted(rel) {
+            let _ = std::fs::remove_file(data_dir.join(format!("{}{}", rel, ENCRYPTED_SUFFIX)));
+            written += 1;
+        } else {
+            // Its directory could not be read; check again on the next flush
+            seen.insert(rel.clone(), *stamp);
+        }
+    }
+
+    state.synced = seen;
+    Ok(written)
+}
+
+/// Encrypt pending changes and wipe the working directory
+fn close_state(state: &mut VaultState) -> Result<(), String> {
+    let result = flush_state(state);
+    // A working directory that disappeared has nothing left to save; forget it so
+    // the next start decrypts a fresh copy. One decrypted from another vault is
+    // left for that vault's recovery (see recover_stale_work_dir).
+    let (lost, foreign) = match &state.work_dir {
+        Some(dir) => {
+            let marker = dir.join(SOURCE_MARKER).is_file();
+            let ours = state
+                .data_dir
+                .as_ref()
+                .is_some_and(|data_dir| decrypted_from(dir, data_dir));
+            (!marker, marker && !ours)
+        }
+        None => (false, false),
+    };
+    if result.is_ok() || lost || foreign {
+        if let Some(work_dir) = state.work_dir.take() {
+            if !foreign {
+                let _ = std::fs::remove_dir_all(&work_dir);
+                info!("[Vault] Working directory wiped");
+            }
+        }
+        state.synced.clear();
+    }
+    result.map(|_| ())
+}
+
+/// Decrypt files another device changed in the vault into the working copy
+/// `files` are relative plaintext paths; returns those whose working copy changed.
+/// A working file with changes that were not encrypted yet is kept and written
+/// back by the next flush, as the sidecar would save over the remote change.
+fn refresh_state(state: &mut VaultState, files: &[String]) -> Vec<String> {
+    let (Some(key), Some(work_dir), Some(data_dir)) = (
+        state.key.as_ref(),
+        state.work_dir.clone(),
+        state.data_dir.clone(),
+    ) else {
+        return Vec::new();
+    };
+    if !decrypted_from(&work_dir, &data_dir) {
+        return Vec::new();
+    }
+
+    let mut refreshed = Vec::new();
+    for rel in files {
+        let target = work_dir.join(rel);
+        let current = target.is_file().then(|| file_stamp(&target));
+        if current != state.synced.get(rel).copied() {
+            info!(
+                "[Vault] Keeping unsaved changes to {} over the synced copy",
+                rel
+            );
+            continue;
+        }
+
+        let content = match std::fs::read(data_dir.join(format!("{}{}", rel, ENCRYPTED_SUFFIX))) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                // Deleted on the other device
+                state.synced.remove(rel);
+                if current.is_some() {
+                    let _ = std::fs::remove_file(&target);
+                    refreshed.push(rel.clone());
+                }
+                continue;
+            }
+            Err(e) => {
+                error!("[Vault] Failed to read {}: {}", rel, e);
+                continue;
+            }
+        };
+        let plaintext = match decrypt_file(key, rel, &content) {
+            Ok(plaintext) => Zeroizing::new(plaintext),
+            Err(e) => {
+                error!("[Vault] Failed to decrypt {}", e);
+                continue;
+            }
+        };
+        // Our own flushes come back as events too
+        if current.is_some() && std::fs::read(&target).is_ok_and(|old| old == *plaintext) {
+            continue;
+        }
+        match write_atomic(&target, &plaintext) {
+            Ok(()) => {
+                state.synced.insert(rel.clone(), file_stamp(&target));
+                refreshed.push(rel.clone());
+            }
+            Err(e) => error!("[Vault] Failed to update {}: {}", rel, e),
+        }
+    }
+    refreshed
+}
+
+/// Directory the sidecar should use as DATA_DIR for `data_dir`
+/// Plain data directories are used as-is; an unlocked vault is decrypted into the
+/// working directory on first use.
+pub(crate) fn sidecar_data_dir(
+    app: &tauri::AppHandle,
+    data_dir: &str,
+) -> Result<String, VaultError> {
+    let data_path = PathBuf::from(data_dir);
+    if !is_vault(&data_path) {
+        return Ok(data_dir.to_string());
+    }
+
+    let work_dir = work_dir_path(app).map_err(VaultError::Io)?;
+    let state = app.state::<Mutex<VaultState>>();
+    let mut state_guard = state.lock().unwrap();
+    if state_guard.key.is_none() || state_guard.data_dir.as_ref() != Some(&data_path) {
+        return Err(VaultError::Locked);
+    }
+
+    let work_dir = match state_guard.work_dir.clone() {
+        Some(dir) => dir,
+        None => open_working_dir(&mut state_guard, &data_path, work_dir).map_err(VaultError::Io)?,
+    };
+    Ok(work_dir.to_string_lossy().to_string())
+}
+
+/// Bring the working copy of the unlocked vault `data_dir` up to date with files
+/// another device changed (see data_watcher.rs); returns the files that changed
+pub(crate) fn refresh_from_vault(
+    app: &tauri::AppHandle,
+    data_dir: &Path,
+    files: &[String],
+) -> Vec<String> {
+    let state = app.state::<Mutex<VaultState>>();
+    let mut state_guard = state.lock().unwrap();
+    if state_guard.data_dir.as_deref() != Some(data_dir) {
+        return Vec::new();
+    }
+    refresh_state(&mut state_guard, files)
+}
+
+/// Whether the key for `data_dir` is loaded
+pub(crate) fn is_unlocked(app: &tauri::AppHandle, data_dir: &Path) -> bool {
+    let state = app.state::<Mutex<VaultState>>();
+    let state_guard = state.lock().unwrap();
+    state_guard.key.is_some() && state_guard.data_dir.as_deref() == Some(data_dir)
+}
+
+/// Point an unlocked vault and its remembered passphrase at the directory it was
+/// moved to (see data_migration.rs); the working directory must be closed
+pub(crate) async fn rebind(app: &tauri::AppHandle, from: &Path, to: &Path) {
+    if !is_unlocked(app, from) {
+        return;
+    }
+    {
+        let state = app.state::<Mutex<VaultState>>();
+        let mut state_guard = state.lock().unwrap();
+        state_guard.data_dir = Some(to.to_path_buf());
+    }
+
+    let handle = app.clone();
+    let (from_key, to_key) = (passphrase_secret_key(from), passphrase_secret_key(to));
+    let result = tauri::async_runtime::spawn_blocking(move || -> Result<(), String> {
+        if let Some(passphrase) = crate::secrets::get(&handle, &from_key)? {
+            let passphrase = Zeroizing::new(passphrase);
+            crate::secrets::set(&handle, &to_key, &passphrase)?;
+            crate::secrets::delete(&handle, &from_key)?;
+        }
+        Ok(())
+    })
+    .await;
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => error!("[Vault] Failed to move the remembered passphrase: {}", e),
+        Err(e) => error!("[Vault] Secret store task failed: {}", e),
+    }
+}
+
+/// Unlock the vault for `data_dir` with a passphrase remembered in the keychain
+/// Does nothing for plain or already unlocked data directories.
+pub(crate) async fn unlock_with_remembered_passphrase(app: &tauri::AppHandle, data_dir: &str) {
+    let data_path = PathBuf::from(data_dir);
+    if !is_vault(&data_path) {
+        return;
+    }
+    if is_unlocked(app, &data_path) {
+        return;
+    }
+
+    let handle = app.clone();
+    let dir = data_path.clone();
+    let result =
+        tauri::async_runtime::spawn_blocking(move || -> Result<Option<DataKey>, String> {
+            let Some(passphrase) = crate::secrets::get(&handle, &passphrase_secret_key(&dir))?
+            else {
+                return Ok(None);
+            };
+            let passphrase = Zeroizing::new(passphrase);
+            let header = read_header(&dir)?;
+            unwrap_key(&header, &passphrase).map(Some)
+        })
+        .await;
+
+    match result {
+        Ok(Ok(Some(key))) => {
+            let state = app.state::<Mutex<VaultState>>();
+            let mut state_guard = state.lock().unwrap();
+            state_guard.key = Some(key);
+            state_guard.data_dir = Some(data_path);
+            info!("[Vault] Unlocked with the remembered passphrase");
+        }
+        Ok(Ok(None)) => {}
+        Ok(Err(e)) => info!("[Vault] Remembered passphrase not used: {}", e),
+        Err(e) => error!("[Vault] Unlock task failed: {}", e),
+    }
+}
+
+/// Store or forget the remembered passphrase for `data_dir`
+async fn remember_passphrase(
+    app: &tauri::AppHandle,
+    data_dir: &Path,
+    passphrase: Option<Zeroizing<String>>,
+) -> Result<(), String> {
+    let app = app.clone();
+    let key = passphrase_secret_key(data_dir);
+    tauri::async_runtime::spawn_blocking(move || match passphrase {
+        Some(passphrase) => crate::secrets::set(&app, &key, &passphrase),
+        None => crate::secrets::delete(&app, &key),
+    })
+    .await
+    .map_err(|e| format!("Secret store task failed: {}", e))?
+}
+
+/// Move the sidecar to VaultLocked so the frontend shows the lock screen
+pub(crate) fn report_locked(app: &tauri::AppHandle, data_dir: &str) {
+    info!("[Vault] {} is locked", data_dir);
+    let data_dir = data_dir.to_string();
+    sidecar_status::transition(app, SidecarPhase::VaultLocked, |state| {
+        state.data_dir = Some(data_dir);
+        state.next_restart_at = None;
+    });
+}
+
+/// Encrypt pending changes and wipe the working directory (after the sidecar stopped)
+/// The key stays loaded, so the next start decrypts a fresh working copy.
+pub(crate) fn close(app: &tauri::AppHandle) {
+    let state = app.state::<Mutex<VaultState>>();
+    let mut state_guard = state.lock().unwrap();
+    if let Err(e) = close_state(&mut state_guard) {
+        error!("[Vault] Failed to save the working directory: {}", e);
+    }
+}
+
+/// Encrypt changes from the working directory into the vault every few seconds
+pub(crate) fn spawn_flusher(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+            let app = app.clone();
+            let result = tauri::async_runtime::spawn_blocking(move || {
+                let state = app.state::<Mutex<VaultState>>();
+                let mut state_guard = state.lock().unwrap();
+                flush_state(&mut state_guard)
+            })
+            .await;
+            match result {
+                Ok(Ok(0)) => {}
+                Ok(Ok(count)) => info!("[Vault] Encrypted {} changed files", count),
+                Ok(Err(e)) => error!("[Vault] Failed to encrypt changes: {}", e),
+                Err(e) => error!("[Vault] Flush task failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Start the sidecar on the current data directory and track readiness in the background
+async fn start_sidecar(app: &tauri::AppHandle, data_dir: String) -> Result<String, String> {
+    crate::supervisor::reset(app);
+    let result = crate::start_bun_sidecar_internal(app, Some(data_dir)).await?;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let _ = crate::wait_for_sidecar_ready(&app).await;
+    });
+    Ok(result)
+}
+
+/// Stop the sidecar so the vault files can be rewritten
+async fn stop_sidecar(app: &tauri::AppHandle) -> Result<(), String> {
+    crate::shutdown::shutdown_sidecar(app).await?;
+    close(app);
+    Ok(())
+}
+
+fn check_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < 8 {
+        return Err("Passphrase must be at least 8 characters".to_string());
+    }
+    Ok(())
+}
+
+/// Whether the current data directory is a vault and whether it is unlocked
+#[tauri::command]
+pub(crate) fn get_vault_status(app: tauri::AppHandle) -> Result<VaultStatus, String> {
+    let data_dir = crate::snapshots::current_data_dir(&app)?;
+    let data_path = PathBuf::from(&data_dir);
+    Ok(VaultStatus {
+        enabled: is_vault(&data_path),
+        unlocked: is_unlocked(&app, &data_path),
+        data_dir,
+    })
+}
+
+/// Unlock the vault with its passphrase and start the sidecar
+/// With `remember`, the passphrase is stored in the keychain (or forgotten when false).
+#[tauri::command]
+pub(crate) async fn unlock_vault(
+    app: tauri::AppHandle,
+    passphrase: String,
+    remember: Option<bool>,
+) -> Result<String, String> {
+    let passphrase = Zeroizing::new(passphrase);
+    let data_dir = crate::snapshots::current_data_dir(&app)?;
+    let data_path = PathBuf::from(&data_dir);
+    if !is_vault(&data_path) {
+        return Err("The data directory is not encrypted".to_string());
+    }
+
+    let header = read_header(&data_path)?;
+    let unwrap_passphrase = passphrase.clone();
+    let key = tauri::async_runtime::spawn_blocking(move || unwrap_key(&header, &unwrap_passphrase))
+        .await
+        .map_err(|e| format!("Unlock failed: {}", e))??;
+
+    if let Some(remember) = remember {
+        let stored = remember.then_some(passphrase);
+        if let Err(e) = remember_passphrase(&app, &data_path, stored).await {
+            error!("[Vault] Failed to update the remembered passphrase: {}", e);
+        }
+    }
+
+    {
+        let state = app.state::<Mutex<VaultState>>();
+        let mut state_guard = state.lock().unwrap();
+        state_guard.key = Some(key);
+        state_guard.data_dir = Some(data_path);
+    }
+    info!("[Vault] Unlocked");
+    start_sidecar(&app, data_dir).await
+}
+
+/// Stop the sidecar, save and wipe the working directory and forget the key
+#[tauri::command]
+pub(crate) async fn lock_vault(app: tauri::AppHandle) -> Result<(), String> {
+    let data_dir = crate::snapshots::current_data_dir(&app)?;
+    if !is_vault(Path::new(&data_dir)) {
+        return Err("The data directory is not encrypted".to_string());
+    }
+
+    stop_sidecar(&app).await?;
+    crate::data_dir_lock::release(&app);
+    {
+        let state = app.state::<Mutex<VaultState>>();
+        let mut state_guard = state.lock().unwrap();
+        state_guard.key = None;
+        state_guard.data_dir = None;
+    }
+    report_locked(&app, &data_dir);
+    Ok(())
+}
+
+/// Encrypt the current data directory with a new passphrase
+#[tauri::command]
+pub(crate) async fn enable_vault(
+    app: tauri::AppHandle,
+    passphrase: String,
+) -> Result<String, String> {
+    let passphrase = Zeroizing::new(passphrase);
+    check_passphrase(&passphrase)?;
+    let data_dir = crate::snapshots::current_data_dir(&app)?;
+    let data_path = PathBuf::from(&data_dir);
+    if is_vault(&data_path) {
+        return Err("The data directory is already encrypted".to_string());
+    }
+
+    stop_sidecar(&app).await?;
+
+    let dir = data_path.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || -> Result<DataKey, String> {
+        let key: DataKey = Zeroizing::new(random_bytes::<32>());
+        let files = plaintext_files(&dir).files;
+
+        // Write every encrypted file before removing any plaintext
+        for (path, rel) in &files {
+            let plaintext = Zeroizing::new(
+                std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", rel, e))?,
+            );
+            let encrypted = encrypt_file(&key, rel, &plaintext)?;
+            write_atomic(
+                &dir.join(format!("{}{}", rel, ENCRYPTED_SUFFIX)),
+                &encrypted,
+            )?;
+        }
+
+        let (kdf, wrapped_key) = wrap_key(&key, &passphrase)?;
+        write_header(
+            &dir,
+            &VaultHeader {
+                version: 1,
+                created_at: now_millis(),
+                kdf,
+                wrapped_key,
+            },
+        )?;
+
+        for (path, rel) in &files {
+            if let Err(e) = std::fs::remove_file(path) {
+                error!("[Vault] Failed to remove plaintext {}: {}", rel, e);
+            }
+        }
+        info!("[Vault] Encrypted {} files", files.len());
+        Ok(key)
+    })
+    .await
+    .map_err(|e| format!("Encryption task failed: {}", e))?;
+
+    match result {
+        Ok(key) => {
+            let state = app.state::<Mutex<VaultState>>();
+            let mut state_guard = state.lock().unwrap();
+            state_guard.key = Some(key);
+            state_guard.data_dir = Some(data_path);
+        }
+        Err(e) => {
+            // Leftover .enc files are harmless without a header; keep the app usable
+            let _ = start_sidecar(&app, data_dir).await;
+            return Err(e);
+        }
+    }
+
+    start_sidecar(&app, data_dir).await
+}
+
+/// Decrypt the vault back into plain files (requires the passphrase)
+#[tauri::command]
+pub(crate) async fn disable_vault(
+    app: tauri::AppHandle,
+    passphrase: String,
+) -> Result<String, String> {
+    let passphrase = Zeroizing::new(passphrase);
+    let data_dir = crate::snapshots::current_data_dir(&app)?;
+    let data_path = PathBuf::from(&data_dir);
+    if !is_vault(&data_path) {
+        return Err("The data directory is not encrypted".to_string());
+    }
+    let header = read_header(&data_path)?;
+    let key = tauri::async_runtime::spawn_blocking(move || unwrap_key(&header, &passphrase))
+        .await
+        .map_err(|e| format!("Decryption failed: {}", e))??;
+
+    stop_sidecar(&app).await?;
+
+    let dir = data_path.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || -> Result<usize, String> {
+        let files = encrypted_files(&dir);
+
+        // Write every plaintext file before removing the vault
+        for (path, rel) in &files {
+            let content =
+                std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+            let plaintext = Zeroizing::new(decrypt_file(&key, rel, &content)?);
+            write_atomic(&dir.join(rel), &plaintext)?;
+        }
+
+        std::fs::remove_file(header_path(&dir))
+            .map_err(|e| format!("Failed to remove vault header: {}", e))?;
+        for (path, rel) in &files {
+            if let Err(e) = std::fs::remove_file(path) {
+                error!("[Vault] Failed to remove {}.enc: {}", rel, e);
+            }
+        }
+        Ok(files.len())
+    })
+    .await
+    .map_err(|e| format!("Decryption task failed: {}", e))?;
+
+    {
+        let state = app.state::<Mutex<VaultState>>();
+        let mut state_guard = state.lock().unwrap();
+        state_guard.key = None;
+        state_guard.data_dir = None;
+    }
+
+    let count = match result {
+        Ok(count) => count,
+        Err(e) => {
+            report_locked(&app, &data_dir);
+            return Err(e);
+        }
+    };
+    info!("[Vault] Decrypted {} files, vault disabled", count);
+    if let Err(e) = remember_passphrase(&app, &data_path, None).await {
+        error!("[Vault] Failed to forget the remembered passphrase: {}", e);
+    }
+    start_sidecar(&app, data_dir).await
+}
+
+/// Change the vault passphrase (only the data key is re-encrypted)
+#[tauri::command]
+pub(crate) async fn change_vault_passphrase(
+    app: tauri::AppHandle,
+    current_passphrase: String,
+    new_passphrase: String,
+) -> Result<(), String> {
+    let current_passphrase = Zeroizing::new(current_passphrase);
+    let new_passphrase = Zeroizing::new(new_passphrase);
+    check_passphrase(&new_passphrase)?;
+    let data_dir = PathBuf::from(crate::snapshots::current_data_dir(&app)?);
+    if !is_vault(&data_dir) {
+        return Err("The data directory is not encrypted".to_string());
+    }
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut header = read_header(&data_dir)?;
+        let key = unwrap_key(&header, &current_passphrase)?;
+        let (kdf, wrapped_key) = wrap_key(&key, &new_passphrase)?;
+        header.kdf = kdf;
+        header.wrapped_key = wrapped_key;
+        write_header(&data_dir, &header)?;
+        info!("[Vault] Passphrase changed");
+
+        // Keep a remembered passphrase working
+        let secret_key = passphrase_secret_key(&data_dir);
+        if matches!(crate::secrets::get(&handle, &secret_key), Ok(Some(_))) {
+            if let Err(e) = crate::secrets::set(&handle, &secret_key, &new_passphrase) {
+                error!("[Vault] Failed to update the remembered passphrase: {}", e);
+            }
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Passphrase change failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [7; 32];
+
+    /// A vault holding `months/2026-01.json`, decrypted into a working directory
+    fn unlocked_vault() -> (tempfile::TempDir, VaultState) {
+        let root = tempfile::tempdir().unwrap();
+        let data_dir = root.path().join("data");
+        let rel = "months/2026-01.json";
+        let encrypted = encrypt_file(&KEY, rel, b"{\"month\":\"2026-01\"}").unwrap();
+        write_atomic(&data_dir.join(format!("{}.enc", rel)), &encrypted).unwrap();
+
+        let mut state = VaultState {
+            data_dir: Some(data_dir.clone()),
+            key: Some(Zeroizing::new(KEY)),
+            ..Default::default()
+        };
+        open_working_dir(&mut state, &data_dir, root.path().join("work")).unwrap();
+        (root, state)
+    }
+
+    fn vault_file(state: &VaultState, rel: &str) -> Option<Vec<u8>> {
+        let path = state
+            .data_dir
+            .as_ref()
+            .unwrap()
+            .join(format!("{}.enc", rel));
+        let content = std::fs::read(path).ok()?;
+        Some(decrypt_file(&KEY, rel, &content).unwrap())
+    }
+
+    fn work_file(state: &VaultState, rel: &str) -> PathBuf {
+        state.work_dir.as_ref().unwrap().join(rel)
+    }
+
+    #[test]
+    fn seal_and_open_round_trip() {
+        let sealed = seal(&KEY, b"secret", b"months/2026-01.json").unwrap();
+        assert_ne!(&sealed[24..], b"secret");
+        let opened = open(&KEY, &sealed, b"months/2026-01.json").unwrap();
+        assert_eq!(opened, b"secret");
+    }
+
+    #[test]
+    fn open_rejects_tampered_data() {
+        let sealed = seal(&KEY, b"secret", b"months/2026-01.json").unwrap();
+
+        let mut flipped = sealed.clone();
+        *flipped.last_mut().unwrap() ^= 1;
+        assert!(open(&KEY, &flipped, b"months/2026-01.json").is_err());
+        assert!(open(&KEY, &sealed[..20], b"months/2026-01.json").is_err());
+        assert!(open(&[8; 32], &sealed, b"months/2026-01.json").is_err());
+    }
+
+    #[test]
+    fn decrypt_file_rejects_a_file_moved_to_another_path() {
+        let encrypted = encrypt_file(&KEY, "months/2026-01.json", b"{}").unwrap();
+        assert_eq!(
+            decrypt_file(&KEY, "months/2026-01.json", &encrypted).unwrap(),
+            b"{}"
+        );
+        assert!(decrypt_file(&KEY, "months/2026-02.json", &encrypted).is_err());
+        assert!(decrypt_file(&KEY, "months/2026-01.json", b"{}").is_err());
+    }
+
+    #[test]
+    fn unwrap_key_rejects_a_wrong_passphrase() {
+        let (kdf, wrapped_key) = wrap_key(&KEY, "correct horse").unwrap();
+        let header = VaultHeader {
+            version: 1,
+            created_at: 0,
+            kdf,
+            wrapped_key,
+        };
+
+        assert_eq!(*unwrap_key(&header, "correct horse").unwrap(), KEY);
+        assert_eq!(
+            unwrap_key(&header, "battery staple").unwrap_err(),
+            "Incorrect passphrase"
+        );
+    }
+
+    #[test]
+    fn flush_encrypts_changed_and_new_files() {
+        let (_root, mut state) = unlocked_vault();
+        assert_eq!(flush_state(&mut state).unwrap(), 0);
+
+        std::fs::write(
+            work_file(&state, "months/2026-01.json"),
+            b"{\"changed\":true}",
+        )
+        .unwrap();
+        std::fs::write(work_file(&state, "version.json"), b"{}").unwrap();
+        assert_eq!(flush_state(&mut state).unwrap(), 2);
+
+        assert_eq!(
+            vault_file(&state, "months/2026-01.json").unwrap(),
+            b"{\"changed\":true}"
+        );
+        assert_eq!(vault_file(&state, "version.json").unwrap(), b"{}");
+        assert_eq!(flush_state(&mut state).unwrap(), 0);
+    }
+
+    #[test]
+    fn flush_removes_files_deleted_in_the_working_copy() {
+        let (_root, mut state) = unlocked_vault();
+        std::fs::remove_file(work_file(&state, "months/2026-01.json")).unwrap();
+
+        assert_eq!(flush_state(&mut state).unwrap(), 1);
+        assert!(vault_file(&state, "months/2026-01.json").is_none());
+    }
+
+    #[test]
+    fn flush_keeps_the_vault_when_the_working_copy_is_gone() {
+        let (_root, mut state) = unlocked_vault();
+        std::fs::remove_dir_all(state.work_dir.as_ref().unwrap()).unwrap();
+
+        assert!(flush_state(&mut state).is_err());
+        assert!(vault_file(&state, "months/2026-01.json").is_some());
+    }
+
+    #[test]
+    fn flush_refuses_a_working_copy_of_another_vault() {
+        let (root, mut state) = unlocked_vault();
+        let other = root.path().join("other");
+        std::fs::write(
+            work_file(&state, SOURCE_MARKER),
+            other.to_string_lossy().as_bytes(),
+        )
+        .unwrap();
+        std::fs::write(work_file(&state, "version.json"), b"{}").unwrap();
+
+        assert!(flush_state(&mut state).is_err());
+        assert!(vault_file(&state, "version.json").is_none());
+
+        // Closing leaves the other vault's plaintext for its own recovery
+        assert!(close_state(&mut state).is_err());
+        assert!(state.work_dir.is_none());
+        assert!(root.path().join("work").join("version.json").is_file());
+    }
+
+    #[test]
+    fn refresh_decrypts_files_changed_on_another_device() {
+        let (_root, mut state) = unlocked_vault();
+        let rel = "months/2026-01.json";
+        let data_dir = state.data_dir.clone().unwrap();
+        let remote = encrypt_file(&KEY, rel, b"{\"remote\":true}").unwrap();
+        write_atomic(&data_dir.join(format!("{}.enc", rel)), &remote).unwrap();
+
+        assert_eq!(refresh_state(&mut state, &[rel.to_string()]), [rel]);
+        assert_eq!(
+            std::fs::read(work_file(&state, rel)).unwrap(),
+            b"{\"remote\":true}"
+        );
+        // The refreshed copy is not encrypted back over the vault
+        assert_eq!(flush_state(&mut state).unwrap(), 0);
+        assert!(refresh_state(&mut state, &[rel.to_string()]).is_empty());
+    }
+
+    #[test]
+    fn refresh_keeps_unsaved_local_changes() {
+        let (_root, mut state) = unlocked_vault();
+        let rel = "months/2026-01.json";
+        let data_dir = state.data_dir.clone().unwrap();
+        std::fs::write(work_file(&state, rel), b"{\"local\":true}").unwrap();
+        let remote = encrypt_file(&KEY, rel, b"{\"remote\":true}").unwrap();
+        write_atomic(&data_dir.join(format!("{}.enc", rel)), &remote).unwrap();
+
+        assert!(refresh_state(&mut state, &[rel.to_string()]).is_empty());
+        assert_eq!(
+            std::fs::read(work_file(&state, rel)).unwrap(),
+            b"{\"local\":true}"
+        );
+    }
+
+    #[test]
+    fn refresh_removes_files_deleted_on_another_device() {
+        let (_root, mut state) = unlocked_vault();
+        let rel = "months/2026-01.json";
+        let data_dir = state.data_dir.clone().unwrap();
+        std::fs::remove_file(data_dir.join(format!("{}.enc", rel))).unwrap();
+
+        assert_eq!(refresh_state(&mut state, &[rel.to_string()]), [rel]);
+        assert!(!work_file(&state, rel).exists());
+        assert_eq!(flush_state(&mut state).unwrap(), 0);
+    }
+}
@@ -0,0 +1,135 @@
+// Webview windows.
+//
+// `main` is created in setup(); `open_window` adds more windows pointed at a
+// frontend route (e.g. /month/2026-01 next to /month/2025-12). All windows
+// load the same frontend and talk to the one sidecar. The window-state plugin
+// saves and restores geometry per label, so a window reopened with the same
+// label comes back where it was.
+
+use tauri::{Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+use tracing::info;
+
+/// Label of the window created at launch
+pub(crate) const MAIN_WINDOW: &str = "main";
+
+/// Label of the quick entry window (see quick_entry.rs); always opens centered
+pub(crate) const QUICK_ENTRY_WINDOW: &str = "quick-entry";
+
+/// Longest accepted window label
+const MAX_LABEL_LEN: usize = 64;
+
+/// Start building a window with devtools enabled according to debug mode
+pub(crate) fn builder<'a, M: Manager<tauri::Wry>>(
+    manager: &'a M,
+    label: &str,
+    url: WebviewUrl,
+    title: &str,
+) -> WebviewWindowBuilder<'a, tauri::Wry, M> {
+    let debug_mode = crate::read_debug_mode(manager.app_handle());
+    info!(
+        "[Windows] Creating window '{}' with devtools: {}",
+        label, debug_mode
+    );
+
+    let window_builder = WebviewWindowBuilder::new(manager, label, url).title(title);
+
+    // Enable devtools based on debug mode setting
+    // Note: In debug builds, devtools is always available. In release builds,
+    // it requires the "devtools" feature flag AND this setting to be true.
+    #[cfg(debug_assertions)]
+    let window_builder = window_builder.devtools(true);
+
+    #[cfg(not(debug_assertions))]
+    let window_builder = window_builder.devtools(debug_mode);
+
+    window_builder
+}
+
+/// Build a regular app window
+pub(crate) fn build<M: Manager<tauri::Wry>>(
+    manager: &M,
+    label: &str,
+    url: WebviewUrl,
+    title: &str,
+) -> Result<WebviewWindow, String> {
+    builder(manager, label, url, title)
+        .inner_size(800.0, 600.0)
+        .min_inner_size(600.0, 400.0)
+        .build()
+        .map_err(|e| format!("Failed to create window '{}': {}", label, e))
+}
+
+/// Validate a frontend route such as `/month/2026-01` and strip the leading slash
+fn check_route(route: &str) -> Result<&str, String> {
+    let path = route
+        .strip_prefix('/')
+        .ok_or_else(|| format!("Route '{}' must start with '/'", route))?;
+    if path.starts_with('/')
+        || path.contains(['\\', ':', '?', '#'])
+        || path.split('/').any(|segment| segment == "..")
+    {
+        return Err(format!("Invalid route '{}'", route));
+    }
+    Ok(path)
+}
+
+/// Window label derived from a route, e.g. `/month/2026-01` -> `month-2026-01`
+fn label_for_route(path: &str) -> String {
+    let label: String = path
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let label = label.trim_matches('-');
+    if label.is_empty() {
+        "home".to_string()
+    } else {
+        label.to_string()
+    }
+}
+
+fn check_label(label: &str) -> Result<(), String> {
+    if label.is_empty() || label.len() > MAX_LABEL_LEN {
+        return Err(format!(
+            "Window label must be 1-{} characters",
+            MAX_LABEL_LEN
+        ));
+    }
+    if !label
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+    {
+        return Err("Window label may only contain letters, digits, '-' and '_'".into());
+    }
+    if label == MAIN_WINDOW || label == QUICK_ENTRY_WINDOW {
+        return Err(format!("Window label '{}' is reserved", label));
+    }
+    Ok(())
+}
+
+/// Open a window on `route`, or focus the window that already has `label`.
+/// Without a label one is derived from the route. Returns the window label.
+#[tauri::command]
+pub(crate) async fn open_window(
+    app: tauri::AppHandle,
+    route: String,
+    label: Option<String>,
+) -> Result<String, String> {
+    let path = check_route(&route)?;
+    let label = match label {
+        Some(label) => label,
+        None => label_for_route(path),
+    };
+    check_label(&label)?;
+
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        window
+            .set_focus()
+            .map_err(|e| format!("Failed to focus window '{}': {}", label, e))?;
+        return Ok(label);
+    }
+
+    let title = format!("Doggy Bag - {}", path);
+    build(&app, &label, WebviewUrl::App(path.into()), &title)?;
+    Ok(label)
+}
@@ -1,6 +1,15 @@
 // API Client Tests
 import { describe, it, expect, vi, beforeEach, afterEach } from 'vitest';
-import { setApiPort, getApiPort, isApiReady, getBaseUrl, apiUrl, apiClient } from './client';
+import {
+  setApiPort,
+  setApiCredentials,
+  resetApiCredentials,
+  getApiPort,
+  isApiReady,
+  getBaseUrl,
+  apiUrl,
+  apiClient,
+} from './client';
 
 // Mock the logger to avoid console noise
 vi.mock('../logger', () => ({
@@ -51,6 +60,7 @@ describe('API Client', () => {
   afterEach(() => {
     globalThis.fetch = originalFetch;
     vi.unstubAllEnvs();
+    resetApiCredentials();
   });
 
   describe('setApiPort', () => {
@@ -391,4 +401,44 @@ describe('API Client', () => {
       expect(apiClient.getBaseUrl).toBe(getBaseUrl);
     });
   });
+
+  describe('setApiCredentials', () => {
+    it('sets the port and sends the token with every request', async () => {
+      setApiCredentials(4100, 'launch-token');
+      expect(getApiPort()).toBe(4100);
+      mockFetch.mockReturnValue(mockResponse({ ok: true }));
+
+      await apiClient.post('/api/items', { name: 'Test' });
+
+      const init = mockFetch.mock.calls[0][1] as RequestInit;
+      const headers = new Headers(init.headers);
+      expect(headers.get('Authorization')).toBe('Bearer launch-token');
+      expect(headers.get('Content-Type')).toBe('application/json');
+    });
+
+    it('never puts the token in a URL', () => {
+      setApiCredentials(4100, 'launch-token');
+      expect(apiUrl('/api/docs/1')).toMatch(/\/api\/docs\/1$/);
+      expect(apiUrl('/api/docs/1')).not.toContain('launch-token');
+    });
+
+    it('keeps the last port when the sidecar listens on a socket', () => {
+      setApiPort(4100);
+      setApiCredentials(null, 'socket-token');
+      expect(getApiPort()).toBe(4100);
+      expect(apiUrl('/api/docs/1')).not.toContain('socket-token');
+    });
+
+    it('sends no token once the credentials are reset', async () => {
+      setApiCredentials(4100, 'launch-token');
+      resetApiCredentials();
+      expect(getApiPort()).toBeNull();
+      mockFetch.mockReturnValue(mockResponse({ ok: true }));
+
+      await apiClient.get('/api/items');
+
+      // No init means no Authorization header
+      expect(mockFetch.mock.calls[0]).toHaveLength(1);
+    });
+  });
 });
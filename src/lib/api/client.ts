@@ -2,22 +2,49 @@ import { createLogger } from '../logger';
 
 const log = createLogger('API Client');
 
-// Dynamic port storage - set by Tauri sidecar-ready event
+// Dynamic port storage - set from the Tauri sidecar-status-changed event
 let apiPort: number | null = null;
 
+// Per-launch token the sidecar requires (from get_sidecar_credentials)
+let apiToken: string | null = null;
+
+// Set once get_sidecar_credentials succeeds - a sidecar on a Unix socket has no port
+let apiConnected = false;
+
 // Check if running in Tauri
 const isTauriEnv = () => {
   return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
 };
 
 /**
- * Set the API port (called when sidecar-ready event is received)
+ * Set the API port (called when the sidecar status reports a usable port)
  */
 export function setApiPort(port: number) {
   apiPort = port;
   log.info(`Port set to ${port}`);
 }
 
+/**
+ * Set the port (null for a sidecar on a socket) and the per-launch token of
+ * the sidecar (from get_sidecar_credentials)
+ */
+export function setApiCredentials(port: number | null, token: string) {
+  if (port !== null) {
+    setApiPort(port);
+  }
+  apiConnected = true;
+  apiToken = token;
+}
+
+/**
+ * Forget the port and token (test-only: the module state otherwise outlives a test)
+ */
+export function resetApiCredentials() {
+  apiPort = null;
+  apiToken = null;
+  apiConnected = false;
+}
+
 /**
  * Get the current API port (null if not yet set)
  */
@@ -26,21 +53,27 @@ export function getApiPort(): number | null {
 }
 
 /**
- * Check if the API client is ready (has a port configured)
+ * Check if the API client is ready (has credentials or a port configured)
  */
 export function isApiReady(): boolean {
-  // In Tauri, we need the port to be set
+  // In Tauri, we need the sidecar to be usable
   if (isTauriEnv()) {
-    return apiPort !== null;
+    return apiConnected || apiPort !== null;
   }
   // In browser dev mode, we use Vite proxy so we're always ready
   return true;
 }
 
+// Fixed origin of the host's dbapi:// protocol, which forwards requests to the
+// sidecar (see src-tauri/src/api_proxy.rs). Windows and Android webviews reach
+// custom protocols through http://<scheme>.localhost.
+const proxyOrigin = () =>
+  /Windows|Android/.test(navigator.userAgent) ? 'http://dbapi.localhost' : 'dbapi://localhost';
+
 export const getBaseUrl = () => {
-  // In Tauri (dev or prod), use the dynamic port from sidecar
-  if (isTauriEnv() && apiPort !== null) {
-    return `http://localhost:${apiPort}`;
+  // In Tauri (dev or prod), go through the proxy so the sidecar's port never matters
+  if (isTauriEnv() && (apiConnected || apiPort !== null)) {
+    return proxyOrigin();
   }
 
   // In browser dev mode (not Tauri), use Vite proxy
@@ -60,12 +93,24 @@ export const getBaseUrl = () => {
 // Helper to build full API URL
 export const apiUrl = (path: string) => `${getBaseUrl()}${path}`;
 
+/**
+ * fetch() against the sidecar - adds the Authorization header once a token is set
+ */
+export function apiFetch(url: string, init?: RequestInit): Promise<Response> {
+  if (!apiToken) {
+    return init ? fetch(url, init) : fetch(url);
+  }
+  const headers = new Headers(init?.headers);
+  headers.set('Authorization', `Bearer ${apiToken}`);
+  return fetch(url, { ...init, headers });
+}
+
 export const apiClient = {
   // Expose getBaseUrl for components that need to construct their own URLs
   getBaseUrl,
 
   async get(path: string) {
-    const response = await fetch(apiUrl(path));
+    const response = await apiFetch(apiUrl(path));
     if (!response.ok) {
       throw new Error(`GET ${path} failed: ${response.statusText}`);
     }
@@ -73,7 +118,7 @@ export const apiClient = {
   },
 
   async post(path: string, body: unknown) {
-    const response = await fetch(apiUrl(path), {
+    const response = await apiFetch(apiUrl(path), {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify(body),
@@ -88,7 +133,7 @@ export const apiClient = {
   },
 
   async put(path: string, id: string, body: unknown) {
-    const response = await fetch(apiUrl(`${path}/${id}`), {
+    const response = await apiFetch(apiUrl(`${path}/${id}`), {
       method: 'PUT',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify(body),
@@ -105,7 +150,7 @@ export const apiClient = {
 
   // Generic PUT for paths that don't follow the /{id} pattern
   async putPath(path: string, body: unknown) {
-    const response = await fetch(apiUrl(path), {
+    const response = await apiFetch(apiUrl(path), {
       method: 'PUT',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify(body),
@@ -119,7 +164,7 @@ export const apiClient = {
   },
 
   async delete(path: string, id: string) {
-    const response = await fetch(apiUrl(`${path}/${id}`), {
+    const response = await apiFetch(apiUrl(`${path}/${id}`), {
       method: 'DELETE',
     });
     if (!response.ok && response.status !== 204) {
@@ -130,7 +175,7 @@ export const apiClient = {
 
   // Generic DELETE for paths that don't follow the /{id} pattern
   async deletePath(path: string) {
-    const response = await fetch(apiUrl(path), {
+    const response = await apiFetch(apiUrl(path), {
       method: 'DELETE',
     });
     if (!response.ok && response.status !== 204) {
@@ -0,0 +1,55 @@
+// Data directory integrity check - mirrors DataDirectoryReport in src-tauri/src/data_integrity.rs
+// The host checks every data file before starting the backend and emits
+// `data-integrity-failed` with this report when one is damaged
+
+export type FileStatus =
+  | { status: 'ok' }
+  | { status: 'empty' }
+  | { status: 'corrupt'; error: string }
+  | { status: 'unexpectedShape'; expected: string }
+  | { status: 'unreadable'; error: string };
+
+export type FileReport = FileStatus & {
+  /** Path relative to the data directory, e.g. `months/2026-01.json` */
+  path: string;
+  size: number;
+};
+
+export interface DataDirectoryReport {
+  dataDir: string;
+  checkedAt: number;
+  files: FileReport[];
+  /** Number of files whose status is not ok */
+  problems: number;
+  /** Newest snapshot with a valid copy of every damaged file */
+  restoreSnapshot: string | null;
+}
+
+async function invoke<T>(command: string, args?: Record<string, unknown>): Promise<T> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<T>(command, args);
+}
+
+/** Check every data file (defaults to the saved data directory) */
+export function verifyDataDirectory(dataDir?: string): Promise<DataDirectoryReport> {
+  return invoke<DataDirectoryReport>('verify_data_directory', { dataDir: dataDir ?? null });
+}
+
+/** Paths of the files that failed the check */
+export function damagedFiles(report: DataDirectoryReport): string[] {
+  return report.files.filter((f) => f.status !== 'ok').map((f) => f.path);
+}
+
+/**
+ * Replace the damaged files with their copies from `report.restoreSnapshot`.
+ * The host stops the backend, restores the files and starts it again.
+ */
+export function restoreDamagedFiles(report: DataDirectoryReport): Promise<string> {
+  if (!report.restoreSnapshot) {
+    return Promise.reject(new Error('No snapshot has a good copy of the damaged files'));
+  }
+  return invoke<string>('restore_snapshot', {
+    id: report.restoreSnapshot,
+    files: damagedFiles(report),
+  });
+}
@@ -0,0 +1,24 @@
+// doggybag:// links and tray shortcuts - see src-tauri/src/deep_link.rs
+// The host parses the link and sends the main window a `navigate` event
+
+export type NavigateTarget =
+  | { target: 'month'; month: string }
+  | { target: 'calendar' }
+  | { target: 'claim'; id: string }
+  | { target: 'bill'; id: string }
+  | { target: 'quickAdd'; month: string };
+
+/** Payload of the `navigate` event: the target plus the route that shows it */
+export type NavigateRequest = NavigateTarget & { route: string };
+
+/** Payload of the `deep-link-rejected` event */
+export interface DeepLinkRejected {
+  url: string;
+  error: string;
+}
+
+/** Navigation requested by the link the app was launched with (only returned once) */
+export async function takePendingNavigation(): Promise<NavigateRequest | null> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<NavigateRequest | null>('take_pending_navigation');
+}
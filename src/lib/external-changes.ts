@@ -0,0 +1,56 @@
+// Reload stores after the data directory was changed outside the app (cloud sync)
+// Payload of the `data-changed-externally` event - mirrors ExternalChange in
+// src-tauri/src/data_watcher.rs
+
+import { get } from 'svelte/store';
+import { loadBills } from '../stores/bills';
+import { loadCategories } from '../stores/categories';
+import { loadIncomes } from '../stores/incomes';
+import { loadPaymentSources } from '../stores/payment-sources';
+import { monthsStore } from '../stores/months';
+import { detailedMonth } from '../stores/detailed-month';
+import { currentMonth } from '../stores/ui';
+import { createLogger } from './logger';
+
+const log = createLogger('External Changes');
+
+export interface ExternalChange {
+  dataDir: string;
+  /** Paths relative to the data directory, e.g. `months/2026-01.json` */
+  files: string[];
+  /** Entity names, e.g. `bills` for `entities/bills.json` */
+  entities: string[];
+  /** Months, e.g. `2026-01` for `months/2026-01.json` */
+  months: string[];
+}
+
+const entityLoaders: Record<string, () => Promise<void>> = {
+  bills: loadBills,
+  categories: loadCategories,
+  incomes: loadIncomes,
+  'payment-sources': loadPaymentSources,
+};
+
+/**
+ * Reload the stores backed by the changed files.
+ * The month views are computed from entities too, so any entity change reloads the open month.
+ */
+export async function reloadExternalChanges(change: ExternalChange): Promise<void> {
+  log.info(`Data changed externally: ${change.files.join(', ')}`);
+
+  const reloads: Promise<void>[] = change.entities
+    .filter((entity) => entity in entityLoaders)
+    .map((entity) => entityLoaders[entity]());
+
+  const month = get(currentMonth);
+  if (change.entities.length > 0 || change.months.includes(month)) {
+    reloads.push(monthsStore.loadMonth(month), detailedMonth.refresh());
+  }
+
+  const results = await Promise.allSettled(reloads);
+  for (const result of results) {
+    if (result.status === 'rejected') {
+      log.error('Failed to reload after external change:', result.reason);
+    }
+  }
+}
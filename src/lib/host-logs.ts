@@ -0,0 +1,35 @@
+// Host and sidecar log files - see src-tauri/src/logging.rs
+// The host keeps JSON-lines logs under the app log dir, one file per day
+
+export type LogLevel = 'ERROR' | 'WARN' | 'INFO' | 'DEBUG' | 'TRACE';
+
+export interface LogEntry {
+  /** Unix timestamp (ms) */
+  timestamp: number;
+  level: LogLevel;
+  source: 'host' | 'sidecar';
+  message: string;
+  /** Stream of sidecar output */
+  stream: 'stdout' | 'stderr' | null;
+}
+
+export interface LogFilter {
+  /** Least severe level to include */
+  level?: 'error' | 'warn' | 'info' | 'debug';
+  source?: 'host' | 'sidecar';
+  /** Text the message must contain (case-insensitive) */
+  text?: string;
+}
+
+/**
+ * Newest log entries matching `filter`, oldest first.
+ * `since` is a Unix timestamp (ms); `limit` defaults to 500 (at most 5000).
+ */
+export async function readLogs(
+  filter?: LogFilter,
+  since?: number,
+  limit?: number
+): Promise<LogEntry[]> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<LogEntry[]>('read_logs', { filter, since, limit });
+}
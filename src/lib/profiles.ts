@@ -0,0 +1,59 @@
+// Budget profiles - mirrors Profile / ProfileList in src-tauri/src/profiles.rs
+// Each profile is a named data directory with its own theme and debug mode
+
+import type { ThemeMode } from '$lib/theme';
+
+export interface Profile {
+  id: string;
+  name: string;
+  dataDirectory: string;
+  /** Theme used while this profile is active (null keeps the current theme) */
+  themeMode: ThemeMode | null;
+  debugMode: boolean;
+  createdAt: number;
+}
+
+export interface ProfileList {
+  activeProfileId: string | null;
+  profiles: Profile[];
+}
+
+async function invoke<T>(command: string, args?: Record<string, unknown>): Promise<T> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<T>(command, args);
+}
+
+export function listProfiles(): Promise<ProfileList> {
+  return invoke<ProfileList>('list_profiles');
+}
+
+/** Without `dataDirectory` the profile gets a new folder next to the default one */
+export function createProfile(name: string, dataDirectory?: string): Promise<Profile> {
+  return invoke<Profile>('create_profile', { name, dataDirectory: dataDirectory ?? null });
+}
+
+export function updateProfile(
+  id: string,
+  changes: { name?: string; themeMode?: ThemeMode }
+): Promise<Profile> {
+  return invoke<Profile>('update_profile', {
+    id,
+    name: changes.name ?? null,
+    themeMode: changes.themeMode ?? null,
+  });
+}
+
+/** Removes the profile from the list; its data directory is kept */
+export function deleteProfile(id: string): Promise<void> {
+  return invoke<void>('delete_profile', { id });
+}
+
+/** Restarts the backend on the profile's data directory and reloads the window */
+export function switchProfile(id: string): Promise<Profile> {
+  return invoke<Profile>('switch_profile', { id });
+}
+
+/** The active profile, if the list has one */
+export function activeProfile(list: ProfileList): Profile | null {
+  return list.profiles.find((p) => p.id === list.activeProfileId) ?? null;
+}
@@ -0,0 +1,80 @@
+// Sidecar lifecycle status - mirrors SidecarStatus in src-tauri/src/sidecar_status.rs
+// Delivered by the `sidecar-status-changed` event and the `get_sidecar_status` command
+
+export type SidecarState =
+  | 'starting'
+  | 'waitingForPort'
+  | 'healthChecking'
+  | 'ready'
+  | 'degraded'
+  | 'stopping'
+  | 'stopped'
+  | 'failed'
+  | 'dataDirLocked'
+  | 'vaultLocked'
+  | 'choosingProfile';
+
+/** Instance holding the data directory lock - mirrors LockInfo in src-tauri/src/data_dir_lock.rs */
+export interface DataDirLockHolder {
+  instanceId: string;
+  hostname: string;
+  pid: number;
+  appVersion: string;
+  acquiredAt: number;
+  heartbeatAt: number;
+}
+
+export interface SidecarStatus {
+  state: SidecarState;
+  /** Failure reason (only set when state is 'failed') */
+  reason?: string;
+  /** Instance using the data directory (only set when state is 'dataDirLocked') */
+  holder?: DataDirLockHolder;
+  /** Unix timestamp (ms) of the last state change */
+  since: number;
+  startedAt: number | null;
+  readyAt: number | null;
+  /** Unix timestamp (ms) of the next automatic restart, while waiting out a backoff */
+  nextRestartAt: number | null;
+  restartCount: number;
+  exitCode: number | null;
+  healthError: string | null;
+  pid: number | null;
+  /** Localhost port, when the sidecar is not on a Unix socket */
+  port: number | null;
+  /** Unix socket path the sidecar listens on (see src-tauri/src/api_socket.rs) */
+  socket: string | null;
+  dataDir: string | null;
+}
+
+/**
+ * Whether the backend can serve requests in this state.
+ * A degraded sidecar is still running and keeps its socket or port.
+ */
+export function isSidecarUsable(status: SidecarStatus): boolean {
+  return (
+    (status.state === 'ready' || status.state === 'degraded') &&
+    (status.port !== null || status.socket !== null)
+  );
+}
+
+/**
+ * Whether the supervisor is waiting to restart a crashed sidecar.
+ */
+export function isSidecarRestarting(status: SidecarStatus): boolean {
+  return status.state === 'stopped' && status.nextRestartAt !== null;
+}
+
+/**
+ * Port and per-launch token of the backend (see src-tauri/src/api_token.rs).
+ * The port is null when the sidecar listens on a socket. Fails until the sidecar is usable.
+ */
+export interface SidecarCredentials {
+  port: number | null;
+  token: string;
+}
+
+export async function getSidecarCredentials(): Promise<SidecarCredentials> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<SidecarCredentials>('get_sidecar_credentials');
+}
@@ -0,0 +1,30 @@
+// Cloud-sync conflict copies - mirrors SyncConflict in src-tauri/src/sync_conflicts.rs
+// Listed by `list_sync_conflicts`, resolved by `resolve_sync_conflict`, and pushed
+// by the `sync-conflicts-detected` event when a sync tool creates a new copy
+
+export type RecordChange = 'onlyLocal' | 'onlyConflict' | 'changed';
+
+export interface RecordDiff {
+  /** Record path, e.g. `bill_instances/abc/occurrences/def` (empty for month-level fields) */
+  path: string;
+  change: RecordChange;
+}
+
+export interface SyncConflict {
+  /** The conflict copy, relative to the data directory */
+  conflictFile: string;
+  /** The file it conflicts with, e.g. `months/2026-01.json` */
+  file: string;
+  kind: 'conflictCopy' | 'notDownloaded';
+  localExists: boolean;
+  /** Set when either version could not be parsed */
+  error: string | null;
+  differences: RecordDiff[];
+  /**
+   * Whether the 'merge' resolution can be used. A merge keeps only the
+   * `onlyLocal` / `onlyConflict` records passed as `keepRecords`.
+   */
+  canMerge: boolean;
+}
+
+export type SyncResolution = 'keepLocal' | 'keepConflict' | 'merge';
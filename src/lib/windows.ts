@@ -0,0 +1,11 @@
+// Extra app windows - see src-tauri/src/windows.rs
+// Every window loads the same frontend and shares the one backend
+
+/**
+ * Open a window on a route such as `/month/2026-01`, or focus the window that already
+ * has `label`. Without a label one is derived from the route. Resolves to the label.
+ */
+export async function openWindow(route: string, label?: string): Promise<string> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<string>('open_window', { route, label: label ?? null });
+}
@@ -2,7 +2,7 @@
 // Full claims lifecycle management with documents and submissions
 
 import { writable, derived } from 'svelte/store';
-import { apiClient, apiUrl } from '$lib/api/client';
+import { apiClient, apiFetch, apiUrl } from '$lib/api/client';
 import type {
   InsuranceClaim,
   ClaimDocument,
@@ -192,7 +192,7 @@ export async function createExpectedExpense(data: ExpectedExpenseData): Promise<
   store.update((s) => ({ ...s, loading: true, error: null }));
 
   try {
-    const response = await fetch(apiUrl('/api/insurance-claims/expected'), {
+    const response = await apiFetch(apiUrl('/api/insurance-claims/expected'), {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify(data),
@@ -221,7 +221,7 @@ export async function updateExpectedExpense(
   store.update((s) => ({ ...s, loading: true, error: null }));
 
   try {
-    const response = await fetch(apiUrl(`/api/insurance-claims/${id}/expected`), {
+    const response = await apiFetch(apiUrl(`/api/insurance-claims/${id}/expected`), {
       method: 'PUT',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify(updates),
@@ -246,7 +246,7 @@ export async function cancelExpectedExpense(id: string): Promise<void> {
   store.update((s) => ({ ...s, loading: true, error: null }));
 
   try {
-    const response = await fetch(apiUrl(`/api/insurance-claims/${id}/expected`), {
+    const response = await apiFetch(apiUrl(`/api/insurance-claims/${id}/expected`), {
       method: 'DELETE',
     });
 
@@ -276,7 +276,7 @@ export async function convertExpectedToClaim(
   store.update((s) => ({ ...s, loading: true, error: null }));
 
   try {
-    const response = await fetch(apiUrl(`/api/insurance-claims/${id}/convert`), {
+    const response = await apiFetch(apiUrl(`/api/insurance-claims/${id}/convert`), {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify(data),
@@ -306,7 +306,7 @@ export async function markBillPaid(id: string, paid: boolean): Promise<Insurance
   store.update((s) => ({ ...s, loading: true, error: null }));
 
   try {
-    const response = await fetch(apiUrl(`/api/insurance-claims/${id}/bill-paid`), {
+    const response = await apiFetch(apiUrl(`/api/insurance-claims/${id}/bill-paid`), {
       method: 'PATCH',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify({ paid }),
@@ -352,7 +352,7 @@ export async function uploadDocument(
       formData.append('notes', notes.trim());
     }
 
-    const response = await fetch(apiUrl(`/api/insurance-claims/${claimId}/documents`), {
+    const response = await apiFetch(apiUrl(`/api/insurance-claims/${claimId}/documents`), {
       method: 'POST',
       body: formData,
     });
@@ -372,6 +372,7 @@ export async function uploadDocument(
   }
 }
 
+/** Links and new windows open through the dbapi:// proxy, which adds the token */
 export function getDocumentUrl(claimId: string, documentId: string): string {
   return apiUrl(`/api/insurance-claims/${claimId}/documents/${documentId}`);
 }
@@ -380,7 +381,7 @@ export async function deleteDocument(claimId: string, documentId: string) {
   store.update((s) => ({ ...s, loading: true, error: null }));
 
   try {
-    const response = await fetch(
+    const response = await apiFetch(
       apiUrl(`/api/insurance-claims/${claimId}/documents/${documentId}`),
       {
         method: 'DELETE',
@@ -427,7 +428,7 @@ export async function createSubmission(
   store.update((s) => ({ ...s, loading: true, error: null }));
 
   try {
-    const response = await fetch(apiUrl(`/api/insurance-claims/${claimId}/submissions`), {
+    const response = await apiFetch(apiUrl(`/api/insurance-claims/${claimId}/submissions`), {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify(data),
@@ -457,7 +458,7 @@ export async function updateSubmission(
   store.update((s) => ({ ...s, loading: true, error: null }));
 
   try {
-    const response = await fetch(
+    const response = await apiFetch(
       apiUrl(`/api/insurance-claims/${claimId}/submissions/${submissionId}`),
       {
         method: 'PUT',
@@ -486,7 +487,7 @@ export async function deleteSubmission(claimId: string, submissionId: string) {
   store.update((s) => ({ ...s, loading: true, error: null }));
 
   try {
-    const response = await fetch(
+    const response = await apiFetch(
       apiUrl(`/api/insurance-claims/${claimId}/submissions/${submissionId}`),
       {
         method: 'DELETE',
@@ -9,6 +9,7 @@ vi.stubGlobal('fetch', mockFetch);
 // Mock the API client
 vi.mock('$lib/api/client', () => ({
   apiUrl: (path: string) => `http://localhost:3000${path}`,
+  apiFetch: (url: string, init?: RequestInit) => (init ? fetch(url, init) : fetch(url)),
 }));
 
 // Mock the logger
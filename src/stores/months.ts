@@ -1,7 +1,7 @@
 // Months Store - Manages monthly budget data
 
 import { writable, derived } from 'svelte/store';
-import { apiUrl } from '$lib/api/client';
+import { apiFetch, apiUrl } from '$lib/api/client';
 import { createLogger } from '$lib/logger';
 
 const log = createLogger('Months Store');
@@ -136,7 +136,7 @@ function createMonthsStore() {
 
       try {
         // Try to load existing month data
-        const response = await fetch(apiUrl(`/api/months/${month}`));
+        const response = await apiFetch(apiUrl(`/api/months/${month}`));
 
         if (response.status === 404) {
           // Month doesn't exist - don't auto-generate, let user decide
@@ -177,7 +177,7 @@ function createMonthsStore() {
       update((state) => ({ ...state, loading: true, error: null }));
 
       try {
-        const syncResponse = await fetch(apiUrl(`/api/months/${month}/sync`), {
+        const syncResponse = await apiFetch(apiUrl(`/api/months/${month}/sync`), {
           method: 'POST',
         });
 
@@ -208,7 +208,7 @@ function createMonthsStore() {
       update((state) => ({ ...state, loading: true, error: null }));
 
       try {
-        const generateResponse = await fetch(apiUrl(`/api/months/${month}/generate`), {
+        const generateResponse = await apiFetch(apiUrl(`/api/months/${month}/generate`), {
           method: 'POST',
         });
 
@@ -237,7 +237,7 @@ function createMonthsStore() {
 
     async refreshSummary(month: string): Promise<void> {
       try {
-        const response = await fetch(apiUrl(`/api/months/${month}/summary`));
+        const response = await apiFetch(apiUrl(`/api/months/${month}/summary`));
 
         if (response.ok) {
           const summary = await response.json();
@@ -258,7 +258,7 @@ function createMonthsStore() {
 
     async updateBankBalances(month: string, balances: Record<string, number>): Promise<void> {
       try {
-        const response = await fetch(apiUrl(`/api/months/${month}/bank-balances`), {
+        const response = await apiFetch(apiUrl(`/api/months/${month}/bank-balances`), {
           method: 'PUT',
           headers: {
             'Content-Type': 'application/json',
@@ -11,6 +11,7 @@ vi.mock('$lib/api/client', () => ({
     delete: vi.fn(),
     getBaseUrl: vi.fn(() => 'http://localhost:3000'),
   },
+  apiFetch: (url: string, init?: RequestInit) => (init ? fetch(url, init) : fetch(url)),
 }));
 
 // Mock fetch for status transition endpoints
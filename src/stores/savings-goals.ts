@@ -2,7 +2,7 @@
 // Manages savings goals state with CRUD operations and status transitions
 
 import { writable, derived } from 'svelte/store';
-import { apiClient } from '$lib/api/client';
+import { apiClient, apiFetch } from '$lib/api/client';
 
 // ============================================================================
 // Types
@@ -230,7 +230,7 @@ export async function pauseGoal(id: string): Promise<SavingsGoal> {
   store.update((s) => ({ ...s, loading: true, error: null }));
 
   try {
-    const response = await fetch(`${apiClient.getBaseUrl()}/api/savings-goals/${id}/pause`, {
+    const response = await apiFetch(`${apiClient.getBaseUrl()}/api/savings-goals/${id}/pause`, {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
     });
@@ -258,7 +258,7 @@ export async function resumeGoal(id: string): Promise<SavingsGoal> {
   store.update((s) => ({ ...s, loading: true, error: null }));
 
   try {
-    const response = await fetch(`${apiClient.getBaseUrl()}/api/savings-goals/${id}/resume`, {
+    const response = await apiFetch(`${apiClient.getBaseUrl()}/api/savings-goals/${id}/resume`, {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
     });
@@ -288,7 +288,7 @@ export async function completeGoal(id: string, completedAt?: string): Promise<Sa
   store.update((s) => ({ ...s, loading: true, error: null }));
 
   try {
-    const response = await fetch(`${apiClient.getBaseUrl()}/api/savings-goals/${id}/complete`, {
+    const response = await apiFetch(`${apiClient.getBaseUrl()}/api/savings-goals/${id}/complete`, {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
       body: completedAt ? JSON.stringify({ completed_at: completedAt }) : undefined,
@@ -317,7 +317,7 @@ export async function abandonGoal(id: string): Promise<SavingsGoal> {
   store.update((s) => ({ ...s, loading: true, error: null }));
 
   try {
-    const response = await fetch(`${apiClient.getBaseUrl()}/api/savings-goals/${id}/abandon`, {
+    const response = await apiFetch(`${apiClient.getBaseUrl()}/api/savings-goals/${id}/abandon`, {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
     });
@@ -345,7 +345,7 @@ export async function archiveGoal(id: string): Promise<SavingsGoal> {
   store.update((s) => ({ ...s, loading: true, error: null }));
 
   try {
-    const response = await fetch(`${apiClient.getBaseUrl()}/api/savings-goals/${id}/archive`, {
+    const response = await apiFetch(`${apiClient.getBaseUrl()}/api/savings-goals/${id}/archive`, {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
     });
@@ -378,7 +378,7 @@ export async function unarchiveGoal(
   store.update((s) => ({ ...s, loading: true, error: null }));
 
   try {
-    const response = await fetch(`${apiClient.getBaseUrl()}/api/savings-goals/${id}/unarchive`, {
+    const response = await apiFetch(`${apiClient.getBaseUrl()}/api/savings-goals/${id}/unarchive`, {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify({ restore_to_status: restoreToStatus }),
@@ -215,6 +215,15 @@ export interface DirectoryValidation {
   hasExistingData: boolean;
   existingFiles: string[];
   error?: string;
+  // Host-side checks (desktop app only, see validate_data_directory)
+  isEncrypted?: boolean;
+  dataVersion?: string | null;
+  freeBytes?: number | null;
+  filesystem?: string | null;
+  isNetworkMount?: boolean;
+  syncProvider?: string | null;
+  lockedBy?: { hostname: string; pid: number } | null;
+  warnings?: string[];
 }
 
 export interface MigrationResult {
@@ -227,7 +236,28 @@ export interface MigrationResult {
   error?: string;
 }
 
-export type MigrationMode = 'copy' | 'fresh' | 'use_existing';
+export type MigrationMode = 'copy' | 'move' | 'fresh' | 'use_existing';
+
+/** Modes supported by the native migrate_data_directory command */
+export type DataMigrationMode = 'copy' | 'move' | 'adopt-existing';
+
+export interface DataMigrationProgress {
+  phase: 'preparing' | 'copying' | 'verifying' | 'switching' | 'removingSource' | 'done';
+  current: number;
+  total: number;
+  file: string | null;
+}
+
+export interface DataMigrationReport {
+  mode: DataMigrationMode;
+  sourceDir: string;
+  destDir: string;
+  entityFilesCopied: number;
+  monthFilesCopied: number;
+  documentFilesCopied: number;
+  bytesCopied: number;
+  sourceRemoved: boolean;
+}
 
 interface SettingsState {
   settings: AppSettings | null;
@@ -287,6 +317,16 @@ export async function loadSettings(): Promise<void> {
  * Validate a directory for use as data storage
  */
 export async function validateDirectory(path: string): Promise<DirectoryValidation> {
+  if (isTauri()) {
+    // The host can also check free space, the mount type and the data directory lock
+    const { invoke } = await import('@tauri-apps/api/core');
+    const result = await invoke<DirectoryValidation & { error: string | null }>(
+      'validate_data_directory',
+      { path }
+    );
+    return { ...result, error: result.error ?? undefined };
+  }
+
   const result = (await apiClient.post('/api/settings/validate-directory', {
     path,
   })) as DirectoryValidation;
@@ -309,6 +349,31 @@ export async function migrateData(
   return result;
 }
 
+/**
+ * Move the data directory natively (Tauri only)
+ * Copies and verifies the data, switches the saved data directory and restarts
+ * the sidecar on the new path. On failure the app keeps using the source.
+ */
+export async function migrateDataDirectory(
+  src: string,
+  dest: string,
+  mode: DataMigrationMode,
+  onProgress?: (progress: DataMigrationProgress) => void
+): Promise<DataMigrationReport> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  const { listen } = await import('@tauri-apps/api/event');
+
+  const unlisten = await listen<DataMigrationProgress>('data-migration-progress', (event) => {
+    onProgress?.(event.payload);
+  });
+  try {
+    log.info(`Migrating data directory (${mode}): ${src} -> ${dest}`);
+    return await invoke<DataMigrationReport>('migrate_data_directory', { src, dest, mode });
+  } finally {
+    unlisten();
+  }
+}
+
 /**
  * Open native folder picker dialog (Tauri only)
  * Returns the selected path or null if cancelled
@@ -539,6 +604,89 @@ export async function toggleDebugMode(): Promise<boolean> {
   return newValue;
 }
 
+// ============================================================================
+// Bill Reminders - read by the Rust host (src-tauri/src/reminders.rs)
+// ============================================================================
+
+export interface ReminderSettings {
+  enabled: boolean;
+  /** Days before the due date for the first reminder */
+  leadDays: number;
+  /** "HH:MM" - notifications wait until quiet hours end (null = no quiet hours) */
+  quietHoursStart: string | null;
+  quietHoursEnd: string | null;
+}
+
+export const DEFAULT_REMINDER_SETTINGS: ReminderSettings = {
+  enabled: true,
+  leadDays: 3,
+  quietHoursStart: null,
+  quietHoursEnd: null,
+};
+
+/**
+ * Get the reminder settings (Tauri only)
+ */
+export async function getReminderSettings(): Promise<ReminderSettings> {
+  if (!isTauri()) return DEFAULT_REMINDER_SETTINGS;
+  try {
+    const { Store } = await import('@tauri-apps/plugin-store');
+    const store = await Store.load('settings.json');
+    return {
+      enabled: ((await store.get('remindersEnabled')) as boolean | undefined) ?? true,
+      leadDays:
+        ((await store.get('reminderLeadDays')) as number | undefined) ??
+        DEFAULT_REMINDER_SETTINGS.leadDays,
+      quietHoursStart: ((await store.get('quietHoursStart')) as string | undefined) ?? null,
+      quietHoursEnd: ((await store.get('quietHoursEnd')) as string | undefined) ?? null,
+    };
+  } catch (e) {
+    log.error('Failed to get reminder settings from Tauri Store:', e);
+    return DEFAULT_REMINDER_SETTINGS;
+  }
+}
+
+/**
+ * Save the reminder settings (Tauri only); the host picks them up on its next check
+ */
+export async function saveReminderSettings(settings: ReminderSettings): Promise<void> {
+  if (!isTauri()) return;
+  const { Store } = await import('@tauri-apps/plugin-store');
+  const store = await Store.load('settings.json');
+  await store.set('remindersEnabled', settings.enabled);
+  await store.set('reminderLeadDays', settings.leadDays);
+  if (settings.quietHoursStart && settings.quietHoursEnd) {
+    await store.set('quietHoursStart', settings.quietHoursStart);
+    await store.set('quietHoursEnd', settings.quietHoursEnd);
+  } else {
+    await store.delete('quietHoursStart');
+    await store.delete('quietHoursEnd');
+  }
+  await store.save();
+}
+
+// ============================================================================
+// Quick Entry Shortcut - registered by the Rust host (src-tauri/src/quick_entry.rs)
+// ============================================================================
+
+/**
+ * Get the global shortcut that opens the quick entry window (null = off)
+ */
+export async function getQuickEntryShortcut(): Promise<string | null> {
+  if (!isTauri()) return null;
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<string | null>('get_quick_entry_shortcut');
+}
+
+/**
+ * Change the quick entry shortcut, e.g. "CmdOrCtrl+Shift+E" (null turns it off)
+ * Rejects with the reason when the shortcut is invalid or taken by another app
+ */
+export async function setQuickEntryShortcut(shortcut: string | null): Promise<void> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  await invoke('set_quick_entry_shortcut', { shortcut });
+}
+
 // Keep devtools control functions for potential future use (e.g., "Open DevTools Now" button)
 // These open/close the devtools panel, but don't affect the "Inspect Element" context menu
 